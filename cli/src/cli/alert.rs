@@ -0,0 +1,198 @@
+use std::{fmt::Display, io::Write, path::PathBuf, str::FromStr};
+
+use clap::{ArgGroup, Parser};
+use comfy_table::{
+    Cell, CellAlignment, Color, ContentArrangement, Table, modifiers::UTF8_ROUND_CORNERS,
+    presets::UTF8_FULL_CONDENSED,
+};
+use lib_frankfurter::{
+    alert::{Condition, Evaluation, Pair, Rule},
+    api::ServerClient,
+};
+use termcolor::StandardStream;
+
+use super::{ExecuteSubcommand, SubcommandBaseModifiers, utils::if_supports_colour};
+
+/// Check alert rules against the latest exchange rates
+///
+/// Fired alerts are printed, and the exit code is set to 6 if any alert fired, e.g.
+/// `frs alert --move EUR/USD=1 --above EUR/GBP=0.9 --high USD/JPY=20`
+#[derive(Debug, Parser)]
+#[command(group(
+    ArgGroup::new("rules")
+        .required(true)
+        .multiple(true)
+        .args(["above", "below", "daily_move", "high", "low", "file"])
+))]
+pub struct Command {
+    /// Fire when the rate of a currency pair crosses above a level, e.g. EUR/USD=1.1
+    #[arg(long, value_name = "PAIR=LEVEL", value_parser = |s: &str| parse_rule(s, Condition::Above))]
+    above: Vec<Rule>,
+
+    /// Fire when the rate of a currency pair crosses below a level, e.g. EUR/USD=1.05
+    #[arg(long, value_name = "PAIR=LEVEL", value_parser = |s: &str| parse_rule(s, Condition::Below))]
+    below: Vec<Rule>,
+
+    /// Fire when the rate of a currency pair moves by more than a percentage in a day, e.g. EUR/USD=1
+    #[arg(
+        long = "move",
+        value_name = "PAIR=PERCENT",
+        value_parser = |s: &str| parse_rule(s, Condition::DailyMove)
+    )]
+    daily_move: Vec<Rule>,
+
+    /// Fire when the rate of a currency pair is higher than over a number of previous days, e.g. EUR/USD=20
+    #[arg(long, value_name = "PAIR=DAYS", value_parser = |s: &str| parse_rule(s, Condition::High))]
+    high: Vec<Rule>,
+
+    /// Fire when the rate of a currency pair is lower than over a number of previous days, e.g. EUR/USD=20
+    #[arg(long, value_name = "PAIR=DAYS", value_parser = |s: &str| parse_rule(s, Condition::Low))]
+    low: Vec<Rule>,
+
+    /// JSON file containing a list of rules, e.g. [{"pair": {"base": "EUR", "target": "USD"}, "condition": {"daily_move": 1.0}}]
+    #[arg(long, value_name = "FILE")]
+    file: Option<PathBuf>,
+
+    #[command(flatten)]
+    modifiers: SubcommandBaseModifiers,
+}
+
+/// Parse a rule of the form `PAIR=VALUE`, using the given function to build the condition.
+fn parse_rule<T: FromStr>(
+    s: &str,
+    condition: fn(T) -> Condition,
+) -> Result<Rule, lib_frankfurter::Error> {
+    let invalid = || lib_frankfurter::Error::InvalidAlertRule(s.to_owned());
+
+    let (pair, value) = s.split_once('=').ok_or_else(invalid)?;
+    let value = value
+        .trim()
+        .trim_end_matches('%')
+        .parse()
+        .map_err(|_| invalid())?;
+
+    Rule::new(Pair::from_str(pair)?, condition(value))
+}
+
+/// Error returned when any of the alert rules fired, so that a non-zero exit code is used.
+#[derive(Debug)]
+pub struct AlertsFired(pub usize);
+
+impl Display for AlertsFired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            1 => write!(f, "1 alert fired"),
+            n => write!(f, "{n} alerts fired"),
+        }
+    }
+}
+
+impl std::error::Error for AlertsFired {}
+
+impl Command {
+    /// Collect all the rules given as arguments or through a file.
+    fn rules(&self) -> anyhow::Result<Vec<Rule>> {
+        let mut rules = [
+            &self.above,
+            &self.below,
+            &self.daily_move,
+            &self.high,
+            &self.low,
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect::<Vec<Rule>>();
+
+        if let Some(path) = &self.file {
+            let from_file: Vec<Rule> = serde_json::from_reader(std::fs::File::open(path)?)?;
+            for rule in from_file {
+                rules.push(Rule::new(rule.pair, rule.condition)?);
+            }
+        }
+
+        Ok(rules)
+    }
+}
+
+impl ExecuteSubcommand for Command {
+    /// Executes the `alert` subcommand.
+    async fn execute(
+        self,
+        server_client: ServerClient,
+        mut stdout: StandardStream,
+    ) -> anyhow::Result<()> {
+        let SubcommandBaseModifiers { json, raw } = self.modifiers;
+
+        let mut evaluations: Vec<(Rule, Option<Evaluation>)> = Vec::new();
+        for rule in self.rules()? {
+            let response = server_client.period(rule.period_request()).await?;
+            let evaluation = rule.check_period(&response);
+            evaluations.push((rule, evaluation));
+        }
+
+        let fired = evaluations
+            .iter()
+            .filter_map(|(_, e)| e.as_ref())
+            .filter(|e| e.fired)
+            .collect::<Vec<&Evaluation>>();
+
+        if json {
+            writeln!(&mut stdout, "{}", serde_json::to_string_pretty(&fired)?)?;
+        } else if raw {
+            stdout.write_all(
+                &fired
+                    .iter()
+                    .flat_map(|e| format!("{e}\r\n").into_bytes())
+                    .collect::<Vec<u8>>(),
+            )?;
+        } else {
+            let mut table = Table::new();
+
+            table
+                .load_preset(UTF8_FULL_CONDENSED)
+                .apply_modifier(UTF8_ROUND_CORNERS)
+                .set_header(vec!["Rule", "Date", "Rate", "Change", "Fired"])
+                .set_content_arrangement(ContentArrangement::Dynamic);
+
+            for (rule, evaluation) in evaluations.iter() {
+                let Some(e) = evaluation else {
+                    table.add_row(vec![
+                        Cell::new(rule).fg(if_supports_colour(&stdout, Color::Green)),
+                        Cell::new("Not enough rates available")
+                            .fg(if_supports_colour(&stdout, Color::DarkGrey)),
+                    ]);
+                    continue;
+                };
+
+                table.add_row(vec![
+                    Cell::new(rule).fg(if_supports_colour(&stdout, Color::Green)),
+                    Cell::new(e.date).fg(if_supports_colour(&stdout, Color::Blue)),
+                    Cell::new(format!("{:.4}", e.rate))
+                        .fg(if_supports_colour(&stdout, Color::Cyan)),
+                    Cell::new(
+                        e.change_percent()
+                            .map(|c| format!("{c:+.2}%"))
+                            .unwrap_or_default(),
+                    )
+                    .set_alignment(CellAlignment::Right)
+                    .fg(if_supports_colour(&stdout, Color::Cyan)),
+                    if e.fired {
+                        Cell::new("yes").fg(if_supports_colour(&stdout, Color::Red))
+                    } else {
+                        Cell::new("no")
+                    }
+                    .set_alignment(CellAlignment::Center),
+                ]);
+            }
+
+            writeln!(&mut stdout, "{table}")?;
+        }
+
+        if fired.is_empty() {
+            Ok(())
+        } else {
+            Err(AlertsFired(fired.len()).into())
+        }
+    }
+}
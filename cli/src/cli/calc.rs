@@ -0,0 +1,91 @@
+use std::io::Write;
+
+use clap::Parser;
+use comfy_table::{
+    Cell, CellAlignment, Color, ContentArrangement, Table, modifiers::UTF8_ROUND_CORNERS,
+    presets::UTF8_FULL_CONDENSED,
+};
+use lib_frankfurter::{ValidDate, api::ServerClient, calc::Calculation};
+use termcolor::StandardStream;
+
+use super::{ExecuteSubcommand, SubcommandBaseModifiers, utils::if_supports_colour};
+
+/// Calculate an expression over amounts of money in different currencies
+///
+/// Amounts can be added and subtracted, multiplied and divided by numbers, and grouped with
+/// brackets, with the result in the currency after `in`, e.g.
+/// `frs calc "120 USD + 45.50 GBP - 10 CHF in EUR"`
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// Expression to calculate, in the currency of its first amount unless given with `in`
+    #[arg(allow_hyphen_values = true)]
+    expression: Calculation,
+
+    /// Date for exchange rates [form: yyyy-mm-dd, min: 1999-01-04, default: today]
+    #[arg(short = 'd', long)]
+    date: Option<ValidDate>,
+
+    #[command(flatten)]
+    modifiers: SubcommandBaseModifiers,
+}
+
+impl ExecuteSubcommand for Command {
+    /// Executes the `calc` subcommand.
+    async fn execute(
+        self,
+        server_client: ServerClient,
+        mut stdout: StandardStream,
+    ) -> anyhow::Result<()> {
+        let SubcommandBaseModifiers { json, raw } = self.modifiers;
+        let response = server_client
+            .convert(self.expression.convert_request(self.date))
+            .await?;
+        let evaluation = self.expression.evaluate(&response)?;
+
+        if json {
+            writeln!(
+                &mut stdout,
+                "{}",
+                serde_json::to_string_pretty(&evaluation)?
+            )?;
+        } else if raw {
+            write!(&mut stdout, "{:.2}\r\n", evaluation.total)?;
+        } else {
+            let target = &evaluation.target;
+            let mut table = Table::new();
+
+            table
+                .load_preset(UTF8_FULL_CONDENSED)
+                .apply_modifier(UTF8_ROUND_CORNERS)
+                .set_header(vec![
+                    String::from("Amount"),
+                    String::from("Rate"),
+                    target.to_string(),
+                ])
+                .set_content_arrangement(ContentArrangement::Dynamic);
+
+            for term in &evaluation.terms {
+                table.add_row(vec![
+                    Cell::new(format!("{:.2} {}", term.amount, term.currency))
+                        .set_alignment(CellAlignment::Right)
+                        .fg(if_supports_colour(&stdout, Color::Green)),
+                    Cell::new(format!("{:.4}", term.rate)),
+                    Cell::new(format!("{:.2}", term.converted))
+                        .set_alignment(CellAlignment::Right)
+                        .fg(if_supports_colour(&stdout, Color::Cyan)),
+                ]);
+            }
+            table.add_row(vec![
+                Cell::new("Total").set_alignment(CellAlignment::Right),
+                Cell::new(evaluation.date),
+                Cell::new(format!("{:.2}", evaluation.total))
+                    .set_alignment(CellAlignment::Right)
+                    .fg(if_supports_colour(&stdout, Color::Cyan)),
+            ]);
+
+            writeln!(&mut stdout, "{table}")?;
+        }
+
+        Ok(())
+    }
+}
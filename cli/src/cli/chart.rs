@@ -0,0 +1,348 @@
+//! Line charts and sparklines for the terminal, drawn with Unicode braille and block characters.
+
+use std::io;
+
+use termcolor::{Color, ColorSpec, WriteColor};
+
+/// Colours of the series, in order, repeating if there are more series than colours.
+const PALETTE: [Color; 6] = [
+    Color::Cyan,
+    Color::Green,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Blue,
+    Color::Red,
+];
+
+/// Characters for increasing levels of a sparkline.
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Values of a labelled series, with one value per x-axis position or [`None`] for gaps.
+pub struct Series {
+    pub label: String,
+    pub values: Vec<Option<f64>>,
+}
+
+/// Get the colour of the series at the given index.
+pub fn colour(index: usize) -> Color {
+    PALETTE[index % PALETTE.len()]
+}
+
+/// Draw the values as a sparkline, with a space for any gaps.
+pub fn sparkline(values: &[Option<f64>]) -> String {
+    let (min, max) = bounds(values.iter().flatten().copied());
+
+    values
+        .iter()
+        .map(|v| match v {
+            Some(v) => SPARKS[scale(*v, min, max, SPARKS.len())],
+            None => ' ',
+        })
+        .collect()
+}
+
+/// Get the minimum and maximum of the values, or zeros if there are none.
+fn bounds(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values
+        .fold(None, |acc: Option<(f64, f64)>, v| match acc {
+            Some((min, max)) => Some((min.min(v), max.max(v))),
+            None => Some((v, v)),
+        })
+        .unwrap_or_default()
+}
+
+/// Scale the value between the minimum and maximum to one of the given number of steps, using the
+/// middle step if all values are equal.
+fn scale(value: f64, min: f64, max: f64, steps: usize) -> usize {
+    if max - min <= f64::EPSILON {
+        return (steps - 1) / 2;
+    }
+
+    (((value - min) / (max - min)) * (steps - 1) as f64).round() as usize
+}
+
+/// Grid of braille characters, each of which is 2 dots wide and 4 dots high.
+struct Canvas {
+    width: usize,
+    height: usize,
+    dots: Vec<u8>,
+    /// Index of the last series drawn in each character, to colour it by.
+    series: Vec<Option<usize>>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            dots: vec![0; width * height],
+            series: vec![None; width * height],
+        }
+    }
+
+    /// Set the dot at the given position, from the top left.
+    fn set(&mut self, x: usize, y: usize, series: usize) {
+        // Bits of the dots in a braille character, by their column and row
+        const BITS: [[u8; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+        let i = (y / 4) * self.width + x / 2;
+        self.dots[i] |= BITS[x % 2][y % 4];
+        self.series[i] = Some(series);
+    }
+
+    /// Draw a line between the given dots, using Bresenham's algorithm.
+    fn line(&mut self, (x0, y0): (usize, usize), (x1, y1): (usize, usize), series: usize) {
+        let (mut x, mut y) = (x0 as isize, y0 as isize);
+        let (x1, y1) = (x1 as isize, y1 as isize);
+        let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+        let (sx, sy) = (if x < x1 { 1 } else { -1 }, if y < y1 { 1 } else { -1 });
+        let mut err = dx + dy;
+
+        loop {
+            self.set(x as usize, y as usize, series);
+            if x == x1 && y == y1 {
+                break;
+            }
+
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Get the characters of each row, along with the series to colour them by.
+    fn rows(&self) -> Vec<Vec<(char, Option<usize>)>> {
+        (0..self.height)
+            .map(|row| {
+                (0..self.width)
+                    .map(|col| {
+                        let i = row * self.width + col;
+                        (
+                            char::from_u32(0x2800 + u32::from(self.dots[i]))
+                                .expect("Invalid braille character"),
+                            self.series[i],
+                        )
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Format the values of an axis with enough decimal places to tell apart values across the range.
+pub fn format_value(value: f64, range: f64) -> String {
+    let decimals = if range > 0.0 {
+        (2 - range.log10().floor() as i32).clamp(0, 6) as usize
+    } else {
+        4
+    };
+    format!("{value:.decimals$}")
+}
+
+/// Write a line chart of the series, sharing the y-axis, with the given labels along the x-axis
+/// and a plot area of the given number of characters.
+pub fn write_chart(
+    out: &mut impl WriteColor,
+    x_labels: &[String],
+    series: &[Series],
+    width: usize,
+    height: usize,
+) -> io::Result<()> {
+    let (min, max) = bounds(
+        series
+            .iter()
+            .flat_map(|s| s.values.iter().flatten().copied()),
+    );
+    let (dots_x, dots_y) = (width * 2, height * 4);
+    let points = x_labels.len();
+
+    // Dot positions of each value, from the top left
+    let position = |i: usize, v: f64| {
+        let x = if points > 1 {
+            (i * (dots_x - 1) + (points - 1) / 2) / (points - 1)
+        } else {
+            0
+        };
+        (x, dots_y - 1 - scale(v, min, max, dots_y))
+    };
+
+    let mut canvas = Canvas::new(width, height);
+    for (index, s) in series.iter().enumerate() {
+        let mut previous = None;
+        for (i, v) in s.values.iter().enumerate() {
+            let Some(v) = v else {
+                previous = None;
+                continue;
+            };
+
+            let current = position(i, *v);
+            match previous {
+                Some(previous) => canvas.line(previous, current, index),
+                None => canvas.set(current.0, current.1, index),
+            }
+            previous = Some(current);
+        }
+    }
+
+    // Y-AXIS - the maximum, middle and minimum values
+    let y_labels: Vec<Option<String>> = (0..height)
+        .map(|row| {
+            let value = if row == 0 {
+                max
+            } else if row == height - 1 {
+                min
+            } else if height >= 3 && row == (height - 1) / 2 {
+                (max + min) / 2.0
+            } else {
+                return None;
+            };
+            Some(format_value(value, max - min))
+        })
+        .collect();
+    let label_width = y_labels
+        .iter()
+        .flatten()
+        .map(String::len)
+        .max()
+        .unwrap_or(0);
+
+    for (row, label) in canvas.rows().into_iter().zip(y_labels) {
+        match label {
+            Some(label) => write!(out, "{label:>label_width$} ┤")?,
+            None => write!(out, "{:label_width$} │", "")?,
+        }
+        for (c, s) in row {
+            match s {
+                Some(s) => {
+                    out.set_color(ColorSpec::new().set_fg(Some(colour(s))))?;
+                    write!(out, "{c}")?;
+                    out.reset()?;
+                }
+                None => write!(out, "{c}")?,
+            }
+        }
+        writeln!(out)?;
+    }
+
+    // X-AXIS - the first, middle and last labels, where they fit
+    writeln!(out, "{:label_width$} └{}", "", "─".repeat(width))?;
+    let mut axis = vec![' '; width];
+    let mut place = |label: &str, start: usize| {
+        for (i, c) in label.chars().enumerate() {
+            if let Some(slot) = axis.get_mut(start + i) {
+                *slot = c;
+            }
+        }
+    };
+    if let (Some(first), Some(last)) = (x_labels.first(), x_labels.last()) {
+        place(first, 0);
+        if points > 1 && first.len() + last.len() < width {
+            place(last, width - last.len());
+
+            let middle = &x_labels[points / 2];
+            let start = (width - middle.len()) / 2;
+            if points > 2
+                && start > first.len() + 1
+                && start + middle.len() + 1 < width - last.len()
+            {
+                place(middle, start);
+            }
+        }
+    }
+    writeln!(
+        out,
+        "{:label_width$}  {}",
+        "",
+        axis.into_iter().collect::<String>().trim_end()
+    )?;
+
+    // LEGEND
+    write!(out, "{:label_width$}  ", "")?;
+    for (index, s) in series.iter().enumerate() {
+        if index > 0 {
+            write!(out, "  ")?;
+        }
+        out.set_color(ColorSpec::new().set_fg(Some(colour(index))))?;
+        write!(out, "■")?;
+        out.reset()?;
+        write!(out, " {}", s.label)?;
+    }
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests_chart {
+    use termcolor::Buffer;
+
+    use super::*;
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(
+            sparkline(&[Some(1.0), Some(2.0), Some(3.0), None, Some(8.0)]),
+            "▁▂▃ █"
+        );
+        assert_eq!(sparkline(&[Some(5.0), Some(5.0)]), "▄▄");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn test_canvas() {
+        let mut canvas = Canvas::new(2, 1);
+        canvas.set(0, 0, 0);
+        canvas.set(1, 3, 1);
+        canvas.line((2, 0), (3, 3), 0);
+
+        let rows = canvas.rows();
+        assert_eq!(rows[0][0], ('⢁', Some(1)));
+        assert_eq!(rows[0].iter().map(|(c, _)| c).collect::<String>(), "⢁⢣");
+    }
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!(format_value(1.09368, 0.02), "1.0937");
+        assert_eq!(format_value(160.26, 3.0), "160.26");
+        assert_eq!(format_value(16000.0, 2000.0), "16000");
+        assert_eq!(format_value(1.5, 0.0), "1.5000");
+    }
+
+    #[test]
+    fn test_write_chart() {
+        let mut out = Buffer::no_color();
+        write_chart(
+            &mut out,
+            &["2024-01-01", "2024-01-02", "2024-01-03"].map(String::from),
+            &[
+                Series {
+                    label: String::from("USD"),
+                    values: vec![Some(1.0), Some(2.0), Some(3.0)],
+                },
+                Series {
+                    label: String::from("GBP"),
+                    values: vec![Some(3.0), None, Some(1.0)],
+                },
+            ],
+            30,
+            3,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(out.into_inner()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 6, "{output}");
+        assert!(lines[0].starts_with("3.00 ┤⠁"), "{output}");
+        assert!(lines[0].ends_with('⠉'), "{output}");
+        assert!(lines[1].starts_with("2.00 ┤"), "{output}");
+        assert!(lines[2].starts_with("1.00 ┤⣀"), "{output}");
+        assert!(lines[2].ends_with('⢀'), "{output}");
+        assert_eq!(lines[3], format!("     └{}", "─".repeat(30)));
+        assert_eq!(lines[4], "      2024-01-01          2024-01-03");
+        assert_eq!(lines[5], "      ■ USD  ■ GBP");
+    }
+}
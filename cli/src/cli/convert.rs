@@ -6,12 +6,17 @@ use comfy_table::{
     presets::UTF8_FULL_CONDENSED,
 };
 use lib_frankfurter::{
-    Currency, CurrencyValue, ValidDate,
+    Currency, CurrencyValue, Quotation, ValidDate,
     api::{self, ServerClient},
+    export::{self, Export},
+    prices::Prices,
 };
 use termcolor::StandardStream;
 
-use super::{ExecuteSubcommand, SubcommandBaseModifiers, utils::if_supports_colour};
+use super::{
+    ExecuteSubcommand, ExportModifiers, PriceModifiers, SubcommandBaseModifiers,
+    utils::{if_supports_colour, parse_amount},
+};
 
 /// Fetch exchange rates between currencies
 #[derive(Debug, Parser)]
@@ -29,29 +34,71 @@ pub struct Command {
     // Attempted solutions:
     // - `num_args` for `targets` -> all args are consumed anyway
     // - `last(true)` for `amount` -> requires that the amount comes after `--`
-    #[arg(short = 'a', long)]
+    // Free-form queries, e.g. `frs 100 usd to eur`, take the amount positionally, see `shorthand`.
+    #[arg(short = 'a', long, value_parser = parse_amount)]
     amount: Option<CurrencyValue>,
+    /// Number of decimal places to send the amount with, lowering the minimum amount accordingly
+    #[arg(
+        long,
+        default_value_t = CurrencyValue::DEFAULT_PRECISION,
+        value_parser = clap::value_parser!(u8).range(0..=i64::from(CurrencyValue::MAX_PRECISION))
+    )]
+    precision: u8,
 
     /// Date for exchange rates [form: yyyy-mm-dd, min: 1999-01-04, default: today]
     #[arg(short = 'd', long)]
     date: Option<ValidDate>,
 
+    /// Quote the amount of the base currency for one unit of each target currency, e.g. EUR per USD
+    #[arg(short = 'i', long, conflicts_with_all = ["amount", "prices"])]
+    inverse: bool,
+    /// Quote the rates for this many units, of the base currency or of the targets if `--inverse`
+    /// is given, e.g. 100 for currencies like JPY
+    #[arg(
+        long,
+        conflicts_with_all = ["amount", "prices"],
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    per: Option<u32>,
+
     #[command(flatten)]
     modifiers: SubcommandBaseModifiers,
+    #[command(flatten)]
+    export: ExportModifiers,
+    #[command(flatten)]
+    prices: PriceModifiers,
+}
+
+impl Command {
+    /// Get the [`Quotation`] to display the rates with, or [`None`] to display the exchanged
+    /// values as returned by the API.
+    fn quotation(&self) -> Option<Quotation> {
+        match (self.inverse, self.per) {
+            (false, None) => None,
+            (false, Some(per)) => Some(Quotation::Direct { per }),
+            (true, per) => Some(Quotation::Indirect {
+                per: per.unwrap_or(1),
+            }),
+        }
+    }
 }
 
-impl From<Command> for api::convert::Request {
-    fn from(value: Command) -> Self {
-        api::convert::Request {
-            amount: value.amount,
-            base: Some(value.base),
+impl TryFrom<&Command> for api::convert::Request {
+    type Error = lib_frankfurter::Error;
+    fn try_from(value: &Command) -> Result<Self, Self::Error> {
+        Ok(api::convert::Request {
+            amount: value
+                .amount
+                .map(|a| a.with_precision(value.precision))
+                .transpose()?,
+            base: Some(value.base.clone()),
             targets: if value.targets.is_empty() {
                 None
             } else {
-                Some(value.targets)
+                Some(value.targets.clone())
             },
             date: value.date,
-        }
+        })
     }
 }
 
@@ -63,9 +110,68 @@ impl ExecuteSubcommand for Command {
         mut stdout: StandardStream,
     ) -> anyhow::Result<()> {
         let SubcommandBaseModifiers { json, raw } = self.modifiers;
-        let response = server_client.convert(self.into()).await?;
+        let format = self.export.format;
+        let quotation = self.quotation();
+        let response = server_client.convert((&self).try_into()?).await?;
+
+        if let Some(format) = self.prices.prices {
+            self.prices.write(format, response.prices(), &mut stdout)?;
+        } else if let Some(quotation) = quotation {
+            let rates = response.quote(quotation);
+
+            if let Some(format) = format {
+                export::write_records(
+                    &mut stdout,
+                    format,
+                    ["date", "base", "currency", "rate", "quote"],
+                    rates.iter().map(|(currency, rate)| {
+                        [
+                            response.date.to_string(),
+                            response.base.to_string(),
+                            currency.to_string(),
+                            rate.to_string(),
+                            quotation.describe(&response.base, currency),
+                        ]
+                    }),
+                )?;
+            } else if json {
+                let output = serde_json::json!({
+                    "base": response.base,
+                    "date": response.date,
+                    "quotation": quotation,
+                    "rates": rates,
+                });
+                writeln!(&mut stdout, "{}", serde_json::to_string_pretty(&output)?)?;
+            } else if raw {
+                stdout.write_all(
+                    &rates
+                        .into_iter()
+                        .flat_map(|(currency, rate)| format!("{currency}\t{rate}\r\n").into_bytes())
+                        .collect::<Vec<u8>>(),
+                )?;
+            } else {
+                let mut table = Table::new();
+                table
+                    .load_preset(UTF8_FULL_CONDENSED)
+                    .apply_modifier(UTF8_ROUND_CORNERS)
+                    .set_header(vec!["Currency", "Rate", "Quote"])
+                    .set_content_arrangement(ContentArrangement::Dynamic)
+                    .add_rows(rates.into_iter().map(|(currency, rate)| {
+                        vec![
+                            Cell::new(&currency)
+                                .set_alignment(CellAlignment::Right)
+                                .fg(if_supports_colour(&stdout, Color::Green)),
+                            Cell::new(format!("{rate:.4}"))
+                                .fg(if_supports_colour(&stdout, Color::Cyan)),
+                            Cell::new(quotation.describe(&response.base, &currency)),
+                        ]
+                    }));
 
-        if json {
+                writeln!(&mut stdout, "{table}")?;
+            }
+        } else if let Some(format) = format {
+            response.export(&mut stdout, format)?;
+        } else if json {
             writeln!(&mut stdout, "{}", serde_json::to_string_pretty(&response)?)?;
         } else if raw {
             stdout.write_all(
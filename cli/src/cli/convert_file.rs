@@ -0,0 +1,476 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow, bail};
+use chrono::NaiveDate;
+use clap::{Parser, builder::TypedValueParser};
+use lib_frankfurter::{
+    Currency, CurrencyValue, ValidDate,
+    api::{self, ServerClient},
+};
+use serde_json::{Map, Value};
+use strum::{Display, EnumString, VariantNames};
+use termcolor::StandardStream;
+
+use super::{ExecuteSubcommand, utils::parse_path_with_format};
+
+/// Convert the amounts of a CSV, TSV or JSON file of transactions to a single currency
+///
+/// Rates are fetched once per distinct date and currency, and each record is written back with
+/// the converted amount, the rate used and the date of the rate, e.g.
+/// `frs convert-file transactions.csv --amount-col amount --currency-col ccy --date-col date --to EUR`
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// File of transactions, ending in .csv, .tsv or .json (an array of objects)
+    #[arg(value_parser = parse_path_with_format::<FileFormat>)]
+    input: (PathBuf, FileFormat),
+
+    /// File to write the converted transactions to, in the same format [default: stdout]
+    #[arg(long, short = 'o')]
+    output: Option<PathBuf>,
+
+    /// Column of the amounts to convert
+    #[arg(long, value_name = "COLUMN")]
+    amount_col: String,
+
+    /// Decimal separator of the amounts, e.g. `,` for `1.234,56`, with the other of `.` and `,` only
+    /// allowed to separate thousands
+    #[arg(
+        long,
+        default_value = ".",
+        value_parser = clap::builder::PossibleValuesParser::new([".", ","])
+            .map(|s| if s == "," { ',' } else { '.' })
+    )]
+    decimal_separator: char,
+
+    /// Column of the currency codes of the amounts, e.g. USD
+    #[arg(long, value_name = "COLUMN")]
+    currency_col: String,
+
+    /// Column of the dates of the transactions, to convert at the rates of each date [default: latest rates]
+    #[arg(long, value_name = "COLUMN")]
+    date_col: Option<String>,
+
+    /// Format of the dates of the transactions, e.g. %d/%m/%Y
+    #[arg(long, default_value = "%Y-%m-%d", requires = "date_col")]
+    date_format: String,
+
+    /// Currency to convert the amounts TO
+    #[arg(long, short = 't', ignore_case = true, default_value_t)]
+    to: Currency,
+
+    /// Number of decimal places to round the converted amounts to
+    #[arg(
+        long,
+        default_value_t = CurrencyValue::DEFAULT_PRECISION,
+        value_parser = clap::value_parser!(u8).range(0..=i64::from(CurrencyValue::MAX_PRECISION))
+    )]
+    precision: u8,
+
+    /// Name of the added column of converted amounts [default: e.g. amount_eur]
+    #[arg(long, value_name = "COLUMN")]
+    converted_col: Option<String>,
+
+    /// Name of the added column of the rates used
+    #[arg(long, value_name = "COLUMN", default_value = "rate")]
+    rate_col: String,
+
+    /// Name of the added column of the dates of the rates used, which may be before the date of a
+    /// transaction if there were no rates published on it
+    #[arg(long, value_name = "COLUMN", default_value = "rate_date")]
+    rate_date_col: String,
+}
+
+/// Format of the file of transactions, by file extension.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display, EnumString, VariantNames)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+enum FileFormat {
+    Csv,
+    Tsv,
+    Json,
+}
+
+/// Parse an amount with the given decimal separator, where the other of `.` and `,` may separate
+/// groups of thousands, e.g. `-1,234.5` or `-1.234,5`.
+///
+/// Returns [`None`] for amounts which don't group thousands correctly, as they are most likely using
+/// the other decimal separator, e.g. `12,50` with `.` as the decimal separator.
+fn parse_number(s: &str, decimal_separator: char) -> Option<f64> {
+    let thousands_separator = if decimal_separator == ',' { '.' } else { ',' };
+    let s = s.trim().replace(' ', "");
+    let (sign, unsigned) = match s.strip_prefix(['-', '+']) {
+        Some(unsigned) => (&s[..1], unsigned),
+        None => ("", s.as_str()),
+    };
+    let (integer, fraction) = unsigned
+        .split_once(decimal_separator)
+        .unwrap_or((unsigned, ""));
+
+    // Groups after the first must have exactly 3 digits, e.g. `1,234` but not `12,50`
+    let mut groups = integer.split(thousands_separator);
+    let first = groups.next().unwrap_or_default();
+    let grouped = !integer.contains(thousands_separator)
+        || ((1..=3).contains(&first.len()) && groups.all(|g| g.len() == 3));
+    let integer = integer.replace(thousands_separator, "");
+    let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if !grouped
+        || !is_digits(&integer)
+        || !is_digits(fraction)
+        || integer.len() + fraction.len() == 0
+    {
+        return None;
+    }
+
+    format!("{sign}{integer}.{fraction}").parse().ok()
+}
+
+/// Round an amount to the given number of decimal places as a [`CurrencyValue`], keeping its sign.
+fn round_amount(amount: f64, precision: u8) -> String {
+    let sign = if amount.is_sign_negative() { "-" } else { "" };
+    match CurrencyValue::new(amount.abs(), precision) {
+        Ok(value) => format!("{sign}{value}"),
+        // Zero, or below the smallest value at the precision, which may still round up to it
+        Err(_) => format!("{amount:.*}", usize::from(precision)),
+    }
+}
+
+/// Fields of a record needed to convert it.
+struct Transaction {
+    amount: f64,
+    currency: Currency,
+    date: Option<ValidDate>,
+}
+
+/// Conversion of a [`Transaction`] to the target currency.
+struct Conversion {
+    /// Converted amount, rounded to the `--precision`
+    amount: String,
+    rate: f64,
+    date: ValidDate,
+}
+
+impl Command {
+    /// Parse the fields of a record, given a function to get the value of a column, and the amount
+    /// if it is already a number rather than text.
+    fn transaction<'a>(
+        &self,
+        field: impl Fn(&str) -> Option<&'a str>,
+        amount: Option<f64>,
+    ) -> anyhow::Result<Transaction> {
+        let get = |column: &str| field(column).ok_or_else(|| anyhow!("missing column '{column}'"));
+
+        let amount = match amount {
+            Some(amount) => amount,
+            None => {
+                let amount = get(&self.amount_col)?;
+                parse_number(amount, self.decimal_separator).ok_or_else(|| {
+                    anyhow!(
+                        "invalid amount '{amount}' with '{}' as the decimal separator",
+                        self.decimal_separator
+                    )
+                })?
+            }
+        };
+        let currency = get(&self.currency_col)?;
+        Ok(Transaction {
+            amount,
+            currency: currency
+                .trim()
+                .to_ascii_uppercase()
+                .parse()
+                .map_err(|_| anyhow!("invalid currency '{currency}'"))?,
+            date: self
+                .date_col
+                .as_deref()
+                .map(|column| {
+                    let date = get(column)?;
+                    let date = NaiveDate::parse_from_str(date.trim(), &self.date_format).map_err(
+                        |_| anyhow!("invalid date '{date}', expected '{}'", self.date_format),
+                    )?;
+                    anyhow::Ok(ValidDate::try_from(date)?)
+                })
+                .transpose()?,
+        })
+    }
+
+    /// Convert the transactions, fetching the rates once per distinct date and currency.
+    async fn convert(
+        &self,
+        server_client: &ServerClient,
+        transactions: &[Transaction],
+    ) -> anyhow::Result<Vec<Conversion>> {
+        let mut rates: BTreeMap<(Option<ValidDate>, &Currency), (f64, ValidDate)> = BTreeMap::new();
+
+        for t in transactions {
+            let key = (t.date, &t.currency);
+            if rates.contains_key(&key) {
+                continue;
+            }
+            if t.currency == self.to {
+                if let Some(date) = t.date {
+                    rates.insert(key, (1.0, date));
+                }
+                continue;
+            }
+
+            let response = server_client
+                .convert(api::convert::Request {
+                    base: Some(t.currency.clone()),
+                    targets: Some(vec![self.to.clone()]),
+                    amount: None,
+                    date: t.date,
+                })
+                .await?;
+            let Some(value) = response.rates.get(&self.to) else {
+                bail!(
+                    "No exchange rate from {} to {} on {}",
+                    t.currency,
+                    self.to,
+                    response.date
+                );
+            };
+            rates.insert(key, (**value / *response.amount, response.date));
+        }
+
+        // Undated rows already in the target currency are dated like the latest rates of the other
+        // rows, fetching them if there are no other rows
+        if transactions
+            .iter()
+            .any(|t| t.date.is_none() && t.currency == self.to)
+        {
+            let date = match rates.iter().find(|((date, _), _)| date.is_none()) {
+                Some((_, (_, date))) => *date,
+                None => {
+                    server_client
+                        .convert(api::convert::Request {
+                            base: Some(self.to.clone()),
+                            ..Default::default()
+                        })
+                        .await?
+                        .date
+                }
+            };
+            rates.insert((None, &self.to), (1.0, date));
+        }
+
+        Ok(transactions
+            .iter()
+            .map(|t| {
+                let (rate, date) = rates[&(t.date, &t.currency)];
+                Conversion {
+                    amount: round_amount(t.amount * rate, self.precision),
+                    rate,
+                    date,
+                }
+            })
+            .collect())
+    }
+
+    /// Name of the column of converted amounts.
+    fn converted_col(&self) -> String {
+        self.converted_col.clone().unwrap_or_else(|| {
+            format!("{}_{}", self.amount_col, self.to.to_string().to_lowercase())
+        })
+    }
+
+    /// Convert a CSV or TSV file, appending the columns of the conversions to each record.
+    async fn convert_delimited(
+        &self,
+        server_client: &ServerClient,
+        path: &Path,
+        delimiter: u8,
+        writer: impl Write,
+    ) -> anyhow::Result<()> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .from_path(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let header = reader.headers()?.clone();
+        for column in [
+            Some(&self.amount_col),
+            Some(&self.currency_col),
+            self.date_col.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if !header.iter().any(|h| h == column) {
+                bail!(
+                    "Column '{column}' not found in the header of {}",
+                    path.display()
+                );
+            }
+        }
+        let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+
+        let transactions = records
+            .iter()
+            .enumerate()
+            .map(|(i, record)| {
+                self.transaction(
+                    |column| {
+                        header
+                            .iter()
+                            .position(|h| h == column)
+                            .and_then(|i| record.get(i))
+                    },
+                    None,
+                )
+                // The header is the first line
+                .map_err(|e| anyhow!("Invalid record on line {}: {e}", i + 2))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let conversions = self.convert(server_client, &transactions).await?;
+
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(delimiter)
+            .from_writer(writer);
+        writer.write_record(header.iter().chain([
+            self.converted_col().as_str(),
+            &self.rate_col,
+            &self.rate_date_col,
+        ]))?;
+        for (record, c) in records.iter().zip(conversions) {
+            writer.write_record(record.iter().chain([
+                c.amount.as_str(),
+                &c.rate.to_string(),
+                &c.date.to_string(),
+            ]))?;
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Convert a JSON array of objects, adding the fields of the conversions to each object.
+    async fn convert_json(
+        &self,
+        server_client: &ServerClient,
+        path: &Path,
+        mut writer: impl Write,
+    ) -> anyhow::Result<()> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut records: Vec<Map<String, Value>> = serde_json::from_str(&contents)
+            .with_context(|| format!("{} must contain an array of objects", path.display()))?;
+
+        let transactions = records
+            .iter()
+            .enumerate()
+            .map(|(i, record)| {
+                // Amounts may be numbers or strings
+                let amount = record.get(&self.amount_col).and_then(Value::as_f64);
+                self.transaction(
+                    |column| match record.get(column) {
+                        Some(Value::String(s)) => Some(s.as_str()),
+                        _ => None,
+                    },
+                    amount,
+                )
+                .map_err(|e| anyhow!("Invalid record at index {i}: {e}"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let conversions = self.convert(server_client, &transactions).await?;
+
+        for (record, c) in records.iter_mut().zip(conversions) {
+            record.insert(
+                self.converted_col(),
+                Value::Number(
+                    c.amount
+                        .parse()
+                        .context("Converted amount is not a number")?,
+                ),
+            );
+            record.insert(self.rate_col.clone(), c.rate.into());
+            record.insert(self.rate_date_col.clone(), c.date.to_string().into());
+        }
+        writeln!(writer, "{}", serde_json::to_string_pretty(&records)?)?;
+
+        Ok(())
+    }
+}
+
+impl ExecuteSubcommand for Command {
+    /// Executes the `convert-file` subcommand.
+    async fn execute(
+        self,
+        server_client: ServerClient,
+        mut stdout: StandardStream,
+    ) -> anyhow::Result<()> {
+        let (path, format) = &self.input;
+        if [&self.rate_col, &self.rate_date_col, &self.converted_col()]
+            .iter()
+            .any(|c| [&self.amount_col, &self.currency_col].contains(c))
+        {
+            bail!("The added columns must have different names to the amount and currency columns");
+        }
+
+        // Convert in full before writing, so that the input can be overwritten
+        let mut output = Vec::new();
+        match format {
+            FileFormat::Csv => {
+                self.convert_delimited(&server_client, path, b',', &mut output)
+                    .await?
+            }
+            FileFormat::Tsv => {
+                self.convert_delimited(&server_client, path, b'\t', &mut output)
+                    .await?
+            }
+            FileFormat::Json => self.convert_json(&server_client, path, &mut output).await?,
+        }
+
+        match &self.output {
+            Some(path) => File::create(path)
+                .and_then(|mut f| f.write_all(&output))
+                .with_context(|| format!("Failed to write {}", path.display()))?,
+            None => stdout.write_all(&output)?,
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests_convert_file {
+    use super::*;
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_number("1,200.50", '.'), Some(1200.5));
+        assert_eq!(parse_number(" -45.00 ", '.'), Some(-45.0));
+        assert_eq!(parse_number("1,234,567", '.'), Some(1_234_567.0));
+        assert_eq!(parse_number(".5", '.'), Some(0.5));
+        assert_eq!(parse_number("12,50", ','), Some(12.5));
+        assert_eq!(parse_number("-1.234,5", ','), Some(-1234.5));
+        assert_eq!(parse_number("1 234,5", ','), Some(1234.5));
+
+        // Ambiguous or invalid
+        for (s, decimal_separator) in [
+            ("12,50", '.'),
+            ("1,2345.6", '.'),
+            ("1234,567", '.'),
+            ("1.200.50", '.'),
+            ("12.50", ','),
+            ("", '.'),
+            ("-", '.'),
+            ("1e5", '.'),
+            ("abc", '.'),
+        ] {
+            assert_eq!(parse_number(s, decimal_separator), None, "{s}");
+        }
+    }
+
+    #[test]
+    fn test_round_amount() {
+        assert_eq!(round_amount(12.345678, 2), "12.35");
+        assert_eq!(round_amount(-12.345678, 3), "-12.346");
+        assert_eq!(round_amount(1.1 * 3.0, 2), "3.30");
+        assert_eq!(round_amount(0.006, 2), "0.01");
+        assert_eq!(round_amount(0.0, 2), "0.00");
+        assert_eq!(round_amount(7.0, 0), "7");
+    }
+}
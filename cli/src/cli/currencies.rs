@@ -5,10 +5,13 @@ use comfy_table::{
     Cell, CellAlignment, Color, ContentArrangement, Table, modifiers::UTF8_ROUND_CORNERS,
     presets::UTF8_FULL_CONDENSED,
 };
-use lib_frankfurter::api::{self, ServerClient};
+use lib_frankfurter::{
+    api::{self, ServerClient},
+    export::Export,
+};
 use termcolor::StandardStream;
 
-use super::{ExecuteSubcommand, SubcommandBaseModifiers};
+use super::{ExecuteSubcommand, ExportModifiers, SubcommandBaseModifiers};
 use crate::cli::utils::if_supports_colour;
 
 /// Fetch the latest supported currency codes and their full names
@@ -16,6 +19,8 @@ use crate::cli::utils::if_supports_colour;
 pub struct Command {
     #[command(flatten)]
     modifiers: SubcommandBaseModifiers,
+    #[command(flatten)]
+    export: ExportModifiers,
 }
 
 impl ExecuteSubcommand for Command {
@@ -29,7 +34,9 @@ impl ExecuteSubcommand for Command {
             .currencies(api::currencies::Request {})
             .await?;
 
-        if self.modifiers.json {
+        if let Some(format) = self.export.format {
+            response.export(&mut stdout, format)?;
+        } else if self.modifiers.json {
             writeln!(&mut stdout, "{}", serde_json::to_string_pretty(&response)?)?;
         } else if self.modifiers.raw {
             stdout.write_all(
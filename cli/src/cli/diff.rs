@@ -0,0 +1,157 @@
+use std::io::Write;
+
+use clap::Parser;
+use comfy_table::{
+    Cell, CellAlignment, Color, ContentArrangement, Table, modifiers::UTF8_ROUND_CORNERS,
+    presets::UTF8_FULL_CONDENSED,
+};
+use lib_frankfurter::{
+    Currency, ValidDate,
+    api::{self, ServerClient},
+};
+use termcolor::StandardStream;
+
+use super::{ExecuteSubcommand, SubcommandBaseModifiers, utils::if_supports_colour};
+
+/// Compare exchange rates between two dates, sorted by the largest changes
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The earlier date to compare exchange rates for [form: yyyy-mm-dd]
+    #[arg(index = 1)]
+    from: ValidDate,
+    /// The later date to compare exchange rates for [form: yyyy-mm-dd, default: today]
+    #[arg(index = 2)]
+    to: Option<ValidDate>,
+
+    /// Base currency to convert FROM
+    #[arg(short = 'b', long, ignore_case = true, default_value_t)]
+    base: Currency,
+    /// Target currencies to convert TO, e.g. USD,AUD [default: all]
+    #[arg(short = 't', long, ignore_case = true, value_delimiter = ',')]
+    targets: Vec<Currency>,
+
+    #[command(flatten)]
+    modifiers: SubcommandBaseModifiers,
+}
+
+impl From<&Command> for api::convert::Request {
+    fn from(value: &Command) -> Self {
+        api::convert::Request {
+            amount: None,
+            base: Some(value.base.clone()),
+            targets: if value.targets.is_empty() {
+                None
+            } else {
+                Some(value.targets.clone())
+            },
+            date: None,
+        }
+    }
+}
+
+impl ExecuteSubcommand for Command {
+    /// Executes the `diff` subcommand.
+    async fn execute(
+        self,
+        server_client: ServerClient,
+        mut stdout: StandardStream,
+    ) -> anyhow::Result<()> {
+        let SubcommandBaseModifiers { json, raw } = self.modifiers;
+        let diff = server_client
+            .diff((&self).into(), self.from, self.to)
+            .await?;
+
+        if json {
+            writeln!(&mut stdout, "{}", serde_json::to_string_pretty(&diff)?)?;
+        } else if raw {
+            let changes = diff.changes.iter().map(|c| {
+                format!(
+                    "{}\t{}\t{}\t{:+.6}\t{:+.4}\r\n",
+                    c.currency, c.from, c.to, c.absolute, c.percent
+                )
+            });
+            let appeared = diff
+                .appeared
+                .iter()
+                .map(|(currency, rate)| format!("{currency}\t\t{rate}\t\t\r\n"));
+            let disappeared = diff
+                .disappeared
+                .iter()
+                .map(|(currency, rate)| format!("{currency}\t{rate}\t\t\t\r\n"));
+            stdout.write_all(
+                &changes
+                    .chain(appeared)
+                    .chain(disappeared)
+                    .flat_map(String::into_bytes)
+                    .collect::<Vec<u8>>(),
+            )?;
+        } else {
+            let mut table = Table::new();
+
+            table
+                .load_preset(UTF8_FULL_CONDENSED)
+                .apply_modifier(UTF8_ROUND_CORNERS)
+                .set_header(vec![
+                    String::from("Currency"),
+                    diff.from_date.to_string(),
+                    diff.to_date.to_string(),
+                    String::from("Change"),
+                    String::from("Change %"),
+                ])
+                .set_content_arrangement(ContentArrangement::Dynamic);
+
+            let currency_cell = |c: &Currency| {
+                Cell::new(c)
+                    .set_alignment(CellAlignment::Right)
+                    .fg(if_supports_colour(&stdout, Color::Green))
+            };
+            let change_colour = |n: f64| {
+                if_supports_colour(
+                    &stdout,
+                    if n > 0.0 {
+                        Color::Green
+                    } else if n < 0.0 {
+                        Color::Red
+                    } else {
+                        Color::Reset
+                    },
+                )
+            };
+
+            for c in diff.changes.iter() {
+                table.add_row(vec![
+                    currency_cell(&c.currency),
+                    Cell::new(format!("{:.4}", c.from))
+                        .fg(if_supports_colour(&stdout, Color::Cyan)),
+                    Cell::new(format!("{:.4}", c.to)).fg(if_supports_colour(&stdout, Color::Cyan)),
+                    Cell::new(format!("{:+.4}", c.absolute))
+                        .set_alignment(CellAlignment::Right)
+                        .fg(change_colour(c.absolute)),
+                    Cell::new(format!("{:+.2}%", c.percent))
+                        .set_alignment(CellAlignment::Right)
+                        .fg(change_colour(c.percent)),
+                ]);
+            }
+            for (currency, rate) in diff.appeared.iter() {
+                table.add_row(vec![
+                    currency_cell(currency),
+                    Cell::new(""),
+                    Cell::new(format!("{rate:.4}")).fg(if_supports_colour(&stdout, Color::Cyan)),
+                    Cell::new("appeared").set_alignment(CellAlignment::Right),
+                ]);
+            }
+            for (currency, rate) in diff.disappeared.iter() {
+                table.add_row(vec![
+                    currency_cell(currency),
+                    Cell::new(format!("{rate:.4}")).fg(if_supports_colour(&stdout, Color::Cyan)),
+                    Cell::new(""),
+                    Cell::new("disappeared").set_alignment(CellAlignment::Right),
+                ]);
+            }
+
+            writeln!(&mut stdout, "{table}")?;
+        }
+
+        Ok(())
+    }
+}
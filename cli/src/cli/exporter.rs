@@ -0,0 +1,290 @@
+use std::{
+    fmt::Write as _,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{Router, extract::State, http::header, response::IntoResponse, routing::get};
+use chrono::{DateTime, Utc};
+use clap::Parser;
+use lib_frankfurter::{
+    Currency,
+    api::{ServerClient, ServerClientRequest, convert},
+};
+use termcolor::StandardStream;
+
+use super::{ExecuteSubcommand, serve::listen};
+
+/// Export the latest exchange rates as Prometheus metrics
+///
+/// Periodically fetches the latest rates and serves them on `/metrics`, along with the age of the
+/// rates and the latency and errors of the requests to the API.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// Base currency to convert FROM
+    #[arg(ignore_case = true, index = 1, default_value_t)]
+    base: Currency,
+    /// Target currencies to convert TO, e.g. USD,AUD [default: all]
+    #[arg(ignore_case = true, index = 2, value_delimiter = ',')]
+    targets: Vec<Currency>,
+
+    /// Address to listen on, e.g. 0.0.0.0:9188 to accept connections from other hosts
+    #[arg(short = 'a', long, default_value = "127.0.0.1:9188")]
+    address: SocketAddr,
+
+    /// Number of seconds between fetching the latest rates
+    #[arg(
+        short = 'i',
+        long,
+        default_value_t = 300,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    interval: u64,
+}
+
+/// Content type of the Prometheus text exposition format.
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// State of the exported metrics, updated after every request for the latest rates.
+#[derive(Default)]
+struct Metrics {
+    /// Most recent successful response, kept when later requests fail.
+    latest: Option<convert::Response>,
+    requests: u64,
+    errors: u64,
+    latency: Option<Duration>,
+    up: bool,
+}
+
+impl Metrics {
+    /// Record the result of a request for the latest rates.
+    fn record(
+        &mut self,
+        result: Result<convert::Response, lib_frankfurter::Error>,
+        latency: Duration,
+    ) {
+        self.requests += 1;
+        self.latency = Some(latency);
+        self.up = result.is_ok();
+
+        match result {
+            Ok(resp) => self.latest = Some(resp),
+            Err(_) => self.errors += 1,
+        }
+    }
+
+    /// Render the metrics in the Prometheus text exposition format.
+    fn render(&self, now: DateTime<Utc>) -> String {
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, f64)]| {
+            if samples.is_empty() {
+                return;
+            }
+
+            // Writing to a `String` can't fail
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} {kind}");
+            for (labels, value) in samples {
+                let _ = writeln!(out, "{name}{labels} {value}");
+            }
+        };
+
+        if let Some(resp) = &self.latest {
+            metric(
+                "frankfurter_rate",
+                "gauge",
+                "Latest exchange rate for one unit of the base currency.",
+                &resp
+                    .rates
+                    .iter()
+                    .map(|(currency, value)| {
+                        (
+                            format!("{{base=\"{}\",target=\"{currency}\"}}", resp.base),
+                            **value / *resp.amount,
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            );
+
+            let date = resp.date.and_time(Default::default()).and_utc();
+            metric(
+                "frankfurter_rate_date_timestamp_seconds",
+                "gauge",
+                "Date of the latest exchange rates, as a Unix timestamp.",
+                &[(String::new(), date.timestamp() as f64)],
+            );
+            metric(
+                "frankfurter_rate_age_seconds",
+                "gauge",
+                "Time since the start of the date of the latest exchange rates.",
+                &[(String::new(), (now - date).num_seconds() as f64)],
+            );
+        }
+
+        if let Some(latency) = self.latency {
+            metric(
+                "frankfurter_request_duration_seconds",
+                "gauge",
+                "Latency of the last request for the latest exchange rates.",
+                &[(String::new(), latency.as_secs_f64())],
+            );
+        }
+        metric(
+            "frankfurter_requests_total",
+            "counter",
+            "Number of requests made for the latest exchange rates.",
+            &[(String::new(), self.requests as f64)],
+        );
+        metric(
+            "frankfurter_request_errors_total",
+            "counter",
+            "Number of failed requests for the latest exchange rates.",
+            &[(String::new(), self.errors as f64)],
+        );
+        metric(
+            "frankfurter_up",
+            "gauge",
+            "Whether the last request for the latest exchange rates succeeded.",
+            &[(String::new(), if self.up { 1.0 } else { 0.0 })],
+        );
+
+        out
+    }
+}
+
+async fn metrics(State(metrics): State<Arc<Mutex<Metrics>>>) -> impl IntoResponse {
+    let body = metrics
+        .lock()
+        .expect("Metrics lock poisoned")
+        .render(Utc::now());
+
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], body)
+}
+
+impl From<&Command> for convert::Request {
+    fn from(value: &Command) -> Self {
+        convert::Request {
+            amount: None,
+            base: Some(value.base.clone()),
+            targets: if value.targets.is_empty() {
+                None
+            } else {
+                Some(value.targets.clone())
+            },
+            date: None,
+        }
+    }
+}
+
+impl ExecuteSubcommand for Command {
+    /// Executes the `exporter` subcommand.
+    async fn execute(
+        self,
+        server_client: ServerClient,
+        mut stdout: StandardStream,
+    ) -> anyhow::Result<()> {
+        let state = Arc::new(Mutex::new(Metrics::default()));
+
+        let request = convert::Request::from(&self);
+        // Fail on invalid arguments up front, rather than only counting the errors
+        request.ensure_valid()?;
+
+        let poller = {
+            let state = state.clone();
+            let mut interval = tokio::time::interval(Duration::from_secs(self.interval));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            tokio::spawn(async move {
+                loop {
+                    interval.tick().await;
+
+                    let start = Instant::now();
+                    let result = server_client.convert(request.clone()).await;
+                    state
+                        .lock()
+                        .expect("Metrics lock poisoned")
+                        .record(result, start.elapsed());
+                }
+            })
+        };
+
+        let router = Router::new()
+            .route("/metrics", get(metrics))
+            .with_state(state);
+
+        let result = listen(self.address, router, &mut stdout).await;
+        poller.abort();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests_exporter {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let mut metrics = Metrics::default();
+        let now = Utc.with_ymd_and_hms(2024, 1, 10, 12, 0, 0).unwrap();
+
+        // NO REQUESTS YET
+        assert_eq!(
+            metrics.render(now),
+            [
+                "# HELP frankfurter_requests_total Number of requests made for the latest exchange rates.",
+                "# TYPE frankfurter_requests_total counter",
+                "frankfurter_requests_total 0",
+                "# HELP frankfurter_request_errors_total Number of failed requests for the latest exchange rates.",
+                "# TYPE frankfurter_request_errors_total counter",
+                "frankfurter_request_errors_total 0",
+                "# HELP frankfurter_up Whether the last request for the latest exchange rates succeeded.",
+                "# TYPE frankfurter_up gauge",
+                "frankfurter_up 0",
+                "",
+            ]
+            .join("\n")
+        );
+
+        // SUCCESS
+        let resp: convert::Response = serde_json::from_str(
+            r#"{"base": "EUR", "amount": 2.0, "date": "2024-01-09", "rates": {"GBP": 1.7, "USD": 2.2}}"#,
+        )
+        .unwrap();
+        metrics.record(Ok(resp), Duration::from_millis(250));
+
+        let rendered = metrics.render(now);
+        for line in [
+            "# TYPE frankfurter_rate gauge",
+            "frankfurter_rate{base=\"EUR\",target=\"GBP\"} 0.85",
+            "frankfurter_rate{base=\"EUR\",target=\"USD\"} 1.1",
+            "frankfurter_rate_date_timestamp_seconds 1704758400",
+            "frankfurter_rate_age_seconds 129600",
+            "frankfurter_request_duration_seconds 0.25",
+            "frankfurter_requests_total 1",
+            "frankfurter_request_errors_total 0",
+            "frankfurter_up 1",
+        ] {
+            assert!(rendered.lines().any(|l| l == line), "{line}\n{rendered}");
+        }
+
+        // FAILURE - the previous rates are kept
+        metrics.record(
+            Err(lib_frankfurter::Error::InvalidDate(String::new())),
+            Duration::from_secs(1),
+        );
+
+        let rendered = metrics.render(now);
+        for line in [
+            "frankfurter_rate{base=\"EUR\",target=\"USD\"} 1.1",
+            "frankfurter_request_duration_seconds 1",
+            "frankfurter_requests_total 2",
+            "frankfurter_request_errors_total 1",
+            "frankfurter_up 0",
+        ] {
+            assert!(rendered.lines().any(|l| l == line), "{line}\n{rendered}");
+        }
+    }
+}
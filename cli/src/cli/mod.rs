@@ -1,17 +1,48 @@
-use std::{env::var, io::Write, process::exit};
+use std::{
+    env::var,
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::PathBuf,
+    process::exit,
+    str::FromStr,
+};
 
-use clap::{Parser, Subcommand};
+use anyhow::Context;
+use clap::{Parser, Subcommand, builder::TypedValueParser as _};
 use enum_dispatch::enum_dispatch;
 use is_terminal::IsTerminal;
-use lib_frankfurter::api::ServerClient;
+use lib_frankfurter::{
+    Currency,
+    api::ServerClient,
+    export::Format,
+    prices::{Price, PriceFormat, PriceWriter},
+};
+use shorthand::Shorthand;
+use strum::VariantNames;
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 use url::Url;
 
+pub mod alert;
+pub mod calc;
+mod chart;
 pub mod convert;
+pub mod convert_file;
 pub mod currencies;
+pub mod diff;
+pub mod exporter;
 pub mod period;
+pub mod plot;
+pub mod serve;
+mod shorthand;
+pub mod status;
 pub mod utils;
 
+/// Name of the binary, for usage in errors.
+const BIN_NAME: &str = "frs";
+
+/// URL of the public Frankfurter API, used if no other URL is provided.
+const PUBLIC_API_URL: &str = "https://api.frankfurter.dev";
+
 #[derive(Parser, Debug)]
 #[command(
     author,
@@ -20,7 +51,7 @@ pub mod utils;
     propagate_version(true),
     verbatim_doc_comment
 )]
-pub struct Cli {
+pub struct Cli<C: Subcommand = Command> {
     /// Specify when to colorize output.
     #[arg(short, long, value_name = "WHEN", default_value_t = clap::ColorChoice::default(), ignore_case = true)]
     color: clap::ColorChoice,
@@ -31,25 +62,76 @@ pub struct Cli {
     #[arg(short, long)]
     url: Option<Url>,
 
+    /// URL of a Frankfurter API to fall back on if the main one is unreachable or fails, e.g. https://api.frankfurter.dev
+    ///
+    /// Can be passed multiple times, with the fallbacks being tried in the given order.
+    #[arg(long, value_name = "URL")]
+    fallback_url: Vec<Url>,
+
     /// Show debug info for errors
     #[arg(short, long, action)]
     debug: bool,
 
     #[command(subcommand)]
     #[allow(missing_docs)]
-    pub command: Command,
+    pub command: C,
 }
 
 #[derive(Subcommand, Debug)]
 #[allow(missing_docs)]
 #[enum_dispatch]
 pub enum Command {
+    Alert(alert::Command),
+    Calc(calc::Command),
     Convert(convert::Command),
+    ConvertFile(convert_file::Command),
     Currencies(currencies::Command),
+    Diff(diff::Command),
+    Exporter(exporter::Command),
     Period(period::Command),
+    Plot(plot::Command),
+    Serve(serve::Command),
+    Status(status::Command),
+}
+
+/// Subcommand of the command line as parsed by [`Cli::parse_args`], which may also be a free-form
+/// conversion query.
+#[derive(Subcommand, Debug)]
+#[allow(missing_docs)]
+pub enum ParsedCommand {
+    #[command(flatten)]
+    Command(Box<Command>),
+    /// Free-form conversion query, e.g. `frs 100 usd to eur`, see [`shorthand`].
+    #[command(external_subcommand)]
+    Shorthand(Vec<String>),
 }
 
 impl Cli {
+    /// Parse the given command line, e.g. [`std::env::args`], exiting with a usage error if it is
+    /// invalid.
+    ///
+    /// A free-form query is parsed into the `convert` subcommand, along with any options after it,
+    /// see [`Shorthand`].
+    pub fn parse_args(args: impl IntoIterator<Item = String>) -> Self {
+        let args: Vec<String> = args.into_iter().collect();
+        let cli = Cli::<ParsedCommand>::parse_from(&args);
+
+        match cli.command {
+            ParsedCommand::Command(command) => Cli {
+                color: cli.color,
+                url: cli.url,
+                fallback_url: cli.fallback_url,
+                debug: cli.debug,
+                command: *command,
+            },
+            // The query is at the end of the command line, after the binary and any options
+            ParsedCommand::Shorthand(words) => {
+                let prefix = &args[..args.len() - words.len()];
+                Shorthand(words).parse(prefix)
+            }
+        }
+    }
+
     /// Get the active [`ColorChoice`].
     ///
     /// Manually converts from [`Self::color`], which is of type [`clap::ColorChoice`], to [`termcolor::ColorChoice`].
@@ -83,6 +165,26 @@ impl Cli {
         StandardStream::stderr(choice)
     }
 
+    /// Build the [`ServerClient`] for the given URL, using the public API if no URL is given.
+    fn server_client(&mut self, url: Option<Url>) -> anyhow::Result<ServerClient> {
+        if self.fallback_url.is_empty() {
+            return Ok(url.map(ServerClient::new).unwrap_or_default());
+        }
+
+        let url = match url {
+            Some(url) => url,
+            None => Url::parse(PUBLIC_API_URL)?,
+        };
+
+        self.fallback_url
+            .drain(..)
+            .fold(ServerClient::builder(url), |builder, fallback| {
+                builder.fallback_url(fallback)
+            })
+            .build()
+            .map_err(Into::into)
+    }
+
     /// Execute command, possibly returning an error.
     pub async fn execute(mut self) {
         let url = self.url.take().or_else(|| {
@@ -92,15 +194,15 @@ impl Cli {
                 .and_then(|u| Url::parse(&u).ok())
         });
 
-        let server_client: ServerClient = url
-            .as_ref()
-            .map(|u| ServerClient::new(u.clone()))
-            .unwrap_or_default();
-
         let stdout = self.stdout();
         let mut stderr = self.stderr();
 
-        if let Err(e) = self.command.execute(server_client, stdout).await {
+        let result = match self.server_client(url) {
+            Ok(server_client) => self.command.execute(server_client, stdout).await,
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = result {
             stderr
                 .set_color(ColorSpec::new().set_fg(Some(Color::Red)))
                 .expect("Couldn't set stderr colour");
@@ -110,11 +212,50 @@ impl Cli {
             }
 
             writeln!(&mut stderr, "Error: {e}").expect("Failed to write to stderr");
-            exit(1);
+            exit(exit_code(&e));
         }
     }
 }
 
+/// Exit codes used to distinguish between the different kinds of errors.
+pub mod exit_codes {
+    /// Any error not covered by the other exit codes.
+    pub const FAILURE: i32 = 1;
+    /// Invalid input, matching the exit code used by `clap` for invalid arguments.
+    pub const INVALID_INPUT: i32 = 2;
+    /// The API could not be reached.
+    pub const NETWORK: i32 = 3;
+    /// The API responded with `404 Not Found`, e.g. for an unknown currency.
+    pub const NOT_FOUND: i32 = 4;
+    /// A temporary failure of the API, where trying again later may succeed.
+    pub const RETRYABLE: i32 = 5;
+    /// At least one of the rules checked by `frs alert` fired.
+    pub const ALERT: i32 = 6;
+}
+
+/// Get the exit code to use for the given error.
+fn exit_code(e: &anyhow::Error) -> i32 {
+    if e.is::<alert::AlertsFired>() {
+        return exit_codes::ALERT;
+    }
+
+    let Some(e) = e.downcast_ref::<lib_frankfurter::Error>() else {
+        return exit_codes::FAILURE;
+    };
+
+    if e.is_client_validation() {
+        exit_codes::INVALID_INPUT
+    } else if e.is_network() {
+        exit_codes::NETWORK
+    } else if e.is_not_found() {
+        exit_codes::NOT_FOUND
+    } else if e.is_retryable() {
+        exit_codes::RETRYABLE
+    } else {
+        exit_codes::FAILURE
+    }
+}
+
 /// Provides a common interface for executing the subcommands.
 #[enum_dispatch(Command)]
 trait ExecuteSubcommand {
@@ -136,3 +277,171 @@ pub struct SubcommandBaseModifiers {
     #[arg(short = 'r', long, action, conflicts_with = "json")]
     pub raw: bool,
 }
+
+#[derive(Debug, Parser)]
+pub struct ExportModifiers {
+    /// Print the output as CSV or TSV with a header row, e.g. to load into a spreadsheet
+    #[arg(
+        short = 'f',
+        long,
+        ignore_case = true,
+        conflicts_with_all = ["json", "raw"],
+        value_parser = clap::builder::PossibleValuesParser::new(Format::VARIANTS)
+            .try_map(|s| s.parse::<Format>())
+    )]
+    pub format: Option<Format>,
+}
+
+#[derive(Debug, Parser)]
+pub struct PeriodExportModifiers {
+    /// Print the output as CSV or TSV with a header row, e.g. to load into a spreadsheet, or the
+    /// exchange rates as prices for GnuCash's price importer, optionally with quoted strings
+    #[arg(
+        short = 'f',
+        long,
+        ignore_case = true,
+        conflicts_with_all = ["json", "raw"],
+        value_parser = clap::builder::PossibleValuesParser::new(PeriodFormat::VARIANTS)
+            .try_map(|s| s.parse::<PeriodFormat>())
+    )]
+    pub format: Option<PeriodFormat>,
+}
+
+/// Format of exported time periods, which may also be one of the GnuCash price formats.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PeriodFormat {
+    Records(Format),
+    Prices(PriceFormat),
+}
+
+impl PeriodFormat {
+    const VARIANTS: &[&str] = &["csv", "tsv", "gnucash", "gnucash-quoted"];
+
+    /// The format of the records, unless prices were requested.
+    pub fn records(self) -> Option<Format> {
+        match self {
+            Self::Records(format) => Some(format),
+            Self::Prices(_) => None,
+        }
+    }
+
+    /// The format of the prices, if prices were requested.
+    pub fn prices(self) -> Option<PriceFormat> {
+        match self {
+            Self::Records(_) => None,
+            Self::Prices(format) => Some(format),
+        }
+    }
+}
+
+impl FromStr for PeriodFormat {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(format) = s.parse() {
+            return Ok(Self::Records(format));
+        }
+
+        match s.parse() {
+            Ok(format @ (PriceFormat::Gnucash | PriceFormat::GnucashQuoted)) => {
+                Ok(Self::Prices(format))
+            }
+            _ => Err(format!("unsupported format '{s}'")),
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct PriceModifiers {
+    /// Print the price of each target currency in the base currency, as directives for plain-text
+    /// accounting, e.g. `P 2024-01-02 USD 0.91 EUR` for hledger, or as CSV for GnuCash's price
+    /// importer, optionally with quoted strings
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        ignore_case = true,
+        conflicts_with_all = ["json", "raw", "format"],
+        value_parser = clap::builder::PossibleValuesParser::new(PriceFormat::VARIANTS)
+            .try_map(|s| s.parse::<PriceFormat>())
+    )]
+    pub prices: Option<PriceFormat>,
+
+    /// Commodity symbol to use for a currency in prices, e.g. USD=$
+    #[arg(long, value_name = "CURRENCY=SYMBOL", requires = "prices", value_parser = parse_commodity)]
+    pub commodity: Vec<(Currency, String)>,
+
+    /// Price file to append the prices to, skipping any it already has for the same date, commodity
+    /// and currency
+    #[arg(long, value_name = "FILE", requires = "prices")]
+    pub append: Option<PathBuf>,
+
+    /// Number of decimal places of prices
+    #[arg(
+        long,
+        value_name = "DECIMALS",
+        default_value_t = 6,
+        requires = "prices"
+    )]
+    pub price_decimals: usize,
+}
+
+/// Parse a commodity symbol for a currency, of the form `CURRENCY=SYMBOL`.
+fn parse_commodity(s: &str) -> Result<(Currency, String), String> {
+    let (currency, symbol) = s
+        .split_once('=')
+        .filter(|(_, symbol)| !symbol.is_empty())
+        .ok_or_else(|| String::from("must be of the form CURRENCY=SYMBOL, e.g. USD=$"))?;
+    let currency = currency
+        .trim()
+        .to_ascii_uppercase()
+        .parse()
+        .map_err(|_| format!("invalid currency '{currency}'"))?;
+
+    Ok((currency, symbol.to_owned()))
+}
+
+impl PriceModifiers {
+    /// Write the prices as directives of the given format, appending any missing ones to the price
+    /// file if one was given, or otherwise to `stdout`.
+    pub fn write(
+        &self,
+        format: PriceFormat,
+        prices: Vec<Price>,
+        stdout: &mut StandardStream,
+    ) -> anyhow::Result<()> {
+        let writer = self.commodity.iter().fold(
+            PriceWriter::new(format).with_decimals(self.price_decimals),
+            |writer, (currency, symbol)| writer.with_symbol(currency.clone(), symbol),
+        );
+
+        let Some(path) = &self.append else {
+            writer.write(stdout, prices)?;
+            return Ok(());
+        };
+
+        let existing = match fs::read(path) {
+            Ok(existing) => existing,
+            Err(e) if e.kind() == ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let mut directives = Vec::new();
+        // Start on a new line if the file doesn't end with one
+        if !existing.is_empty() && !existing.ends_with(b"\n") {
+            directives.push(b'\n');
+        }
+        let count = writer
+            .with_existing(existing.as_slice())?
+            .write(&mut directives, prices)?;
+
+        if count > 0 {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| file.write_all(&directives))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        writeln!(stdout, "Appended {count} prices to {}", path.display())?;
+
+        Ok(())
+    }
+}
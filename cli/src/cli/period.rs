@@ -1,6 +1,6 @@
-use std::io::Write;
+use std::{collections::BTreeMap, io::Write};
 
-use clap::Parser;
+use clap::{CommandFactory, Parser, error::ErrorKind};
 use comfy_table::{
     Cell, CellAlignment, Color, ContentArrangement, Table, modifiers::UTF8_ROUND_CORNERS,
     presets::UTF8_FULL_CONDENSED,
@@ -8,10 +8,20 @@ use comfy_table::{
 use lib_frankfurter::{
     Currency, CurrencyValue, ValidDate,
     api::{self, ServerClient},
+    export::Export,
+    prices::Prices,
 };
 use termcolor::StandardStream;
 
-use super::{ExecuteSubcommand, SubcommandBaseModifiers, utils::if_supports_colour};
+use super::{
+    BIN_NAME, Cli, ExecuteSubcommand, PeriodExportModifiers, PeriodFormat, PriceModifiers,
+    SubcommandBaseModifiers,
+    chart::{self, Series},
+    utils::{if_supports_colour, parse_amount, terminal_width},
+};
+
+/// Number of rows of the plot area of charts.
+const CHART_HEIGHT: usize = 15;
 
 /// Fetch exchange rates between currencies over a given time period
 #[derive(Debug, Parser)]
@@ -25,8 +35,21 @@ pub struct Command {
     targets: Vec<Currency>,
 
     /// A number representing the amount of the base currency to show exchange rates for [default: 1]
-    #[arg(short = 'a', long, default_value = "1", next_line_help(true))]
+    #[arg(
+        short = 'a',
+        long,
+        default_value = "1",
+        value_parser = parse_amount,
+        next_line_help(true)
+    )]
     amount: Option<CurrencyValue>,
+    /// Number of decimal places to send the amount with, lowering the minimum amount accordingly
+    #[arg(
+        long,
+        default_value_t = CurrencyValue::DEFAULT_PRECISION,
+        value_parser = clap::value_parser!(u8).range(0..=i64::from(CurrencyValue::MAX_PRECISION))
+    )]
+    precision: u8,
 
     /// The start date to fetch exchange rates for [form: yyyy-mm-dd, alias: from, default: today]
     #[arg(long, short = 's', alias = "from", next_line_help(true))]
@@ -41,19 +64,39 @@ pub struct Command {
     )]
     end: Option<ValidDate>,
 
+    /// Show a row per date and a column per currency, instead of a row per date and currency
+    #[arg(short = 'p', long, conflicts_with_all = ["raw", "prices"])]
+    pivot: bool,
+
+    /// Draw a line chart of the exchange rates in the terminal
+    #[arg(long, conflicts_with_all = ["pivot", "sparklines", "json", "raw", "format", "prices"])]
+    chart: bool,
+
+    /// Add a column to the table with a sparkline of each currency's trend up to each date
+    #[arg(long, conflicts_with_all = ["pivot", "json", "raw", "format", "prices"])]
+    sparklines: bool,
+
     #[command(flatten)]
     modifiers: SubcommandBaseModifiers,
+    #[command(flatten)]
+    export: PeriodExportModifiers,
+    #[command(flatten)]
+    prices: PriceModifiers,
 }
 
-impl From<&Command> for api::period::Request {
-    fn from(value: &Command) -> Self {
-        api::period::Request {
-            amount: value.amount,
+impl TryFrom<&Command> for api::period::Request {
+    type Error = lib_frankfurter::Error;
+    fn try_from(value: &Command) -> Result<Self, Self::Error> {
+        Ok(api::period::Request {
+            amount: value
+                .amount
+                .map(|a| a.with_precision(value.precision))
+                .transpose()?,
             base: Some(value.base.clone()),
             targets: Some(value.targets.clone()),
             start_date: value.start.unwrap_or_else(ValidDate::max),
             end_date: value.end,
-        }
+        })
     }
 }
 
@@ -65,9 +108,79 @@ impl ExecuteSubcommand for Command {
         mut stdout: StandardStream,
     ) -> anyhow::Result<()> {
         let SubcommandBaseModifiers { json, raw } = self.modifiers;
-        let response = server_client.period((&self).into()).await?;
+        let format = self.export.format.and_then(PeriodFormat::records);
+        let prices = self.export.format.and_then(PeriodFormat::prices);
+        // Prices are always per date and currency, `--prices` is rejected with `--pivot` by clap
+        if self.pivot
+            && let Some(prices) = prices
+        {
+            <Cli>::command()
+                .bin_name(BIN_NAME)
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    format!("the argument '--format {prices}' cannot be used with '--pivot'"),
+                )
+                .exit()
+        }
+        let response = server_client.period((&self).try_into()?).await?;
 
-        if json {
+        if let Some(format) = prices.or(self.prices.prices) {
+            self.prices.write(format, response.prices(), &mut stdout)?;
+        } else if self.chart {
+            let pivot = response.pivot();
+            let dates: Vec<String> = pivot.rows.iter().map(|(d, _)| d.to_string()).collect();
+            let series: Vec<Series> = pivot
+                .currencies
+                .iter()
+                .enumerate()
+                .map(|(i, currency)| Series {
+                    label: currency.to_string(),
+                    values: pivot.rows.iter().map(|(_, v)| v[i].map(|v| *v)).collect(),
+                })
+                .collect();
+            // Leave space for the labels of the y-axis
+            let width = terminal_width().saturating_sub(12).max(10);
+
+            chart::write_chart(&mut stdout, &dates, &series, width, CHART_HEIGHT)?;
+        } else if self.pivot {
+            let pivot = response.pivot();
+
+            if let Some(format) = format {
+                pivot.export(&mut stdout, format)?;
+            } else if json {
+                writeln!(&mut stdout, "{}", serde_json::to_string_pretty(&pivot)?)?;
+            } else {
+                let mut table = Table::new();
+
+                table
+                    .load_preset(UTF8_FULL_CONDENSED)
+                    .apply_modifier(UTF8_ROUND_CORNERS)
+                    .set_header(std::iter::once(Cell::new("Date")).chain(
+                        pivot.currencies.iter().map(|c| {
+                            Cell::new(c)
+                                .set_alignment(CellAlignment::Center)
+                                .fg(if_supports_colour(&stdout, Color::Green))
+                        }),
+                    ))
+                    .set_content_arrangement(ContentArrangement::Dynamic);
+
+                for (date, values) in pivot.rows {
+                    table.add_row(
+                        std::iter::once(
+                            Cell::new(date).fg(if_supports_colour(&stdout, Color::Blue)),
+                        )
+                        .chain(values.into_iter().map(|v| {
+                            Cell::new(v.map(|v| v.to_string()).unwrap_or_default())
+                                .fg(if_supports_colour(&stdout, Color::Cyan))
+                        })),
+                    );
+                }
+
+                writeln!(&mut stdout, "{table}")?;
+            }
+        } else if let Some(format) = format {
+            response.export(&mut stdout, format)?;
+        } else if json {
             writeln!(&mut stdout, "{}", serde_json::to_string_pretty(&response)?)?;
         } else if raw {
             for (date, map) in response.rates.into_iter() {
@@ -80,36 +193,67 @@ impl ExecuteSubcommand for Command {
                 )?;
             }
         } else {
+            // The sparkline of each currency over the whole period, of which each row shows the
+            // part up to its date, so that all rows of a currency share the same scale
+            let trends: BTreeMap<Currency, (usize, Vec<char>)> = if self.sparklines {
+                let pivot = response.pivot();
+                pivot
+                    .currencies
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, currency)| {
+                        let values: Vec<Option<f64>> =
+                            pivot.rows.iter().map(|(_, v)| v[i].map(|v| *v)).collect();
+                        (currency, (i, chart::sparkline(&values).chars().collect()))
+                    })
+                    .collect()
+            } else {
+                BTreeMap::new()
+            };
+            let row = |stdout: &StandardStream,
+                       day: usize,
+                       date: Option<ValidDate>,
+                       currency: Currency,
+                       value: CurrencyValue| {
+                let mut cells = vec![
+                    date.map(|date| Cell::new(date).fg(if_supports_colour(stdout, Color::Blue)))
+                        .unwrap_or_else(|| Cell::new("")),
+                    Cell::new(&currency)
+                        .set_alignment(CellAlignment::Center)
+                        .fg(if_supports_colour(stdout, Color::Green)),
+                    Cell::new(value).fg(if_supports_colour(stdout, Color::Cyan)),
+                ];
+                if let Some((i, trend)) = trends.get(&currency) {
+                    cells.push(
+                        Cell::new(trend[..=day].iter().collect::<String>())
+                            .fg(if_supports_colour(stdout, chart_colour(*i))),
+                    );
+                }
+                cells
+            };
+
             let mut table = Table::new();
 
             table
                 .load_preset(UTF8_FULL_CONDENSED)
                 .apply_modifier(UTF8_ROUND_CORNERS)
-                .set_header(vec!["Date", "Currency", "Value"])
+                .set_header(if self.sparklines {
+                    vec!["Date", "Currency", "Value", "Trend"]
+                } else {
+                    vec!["Date", "Currency", "Value"]
+                })
                 .set_content_arrangement(ContentArrangement::Dynamic);
 
-            for (date, map) in response.rates.into_iter() {
+            for (day, (date, map)) in response.rates.into_iter().enumerate() {
                 let mut iter = map.into_iter();
                 let first = iter
                     .next()
                     .unwrap_or_else(|| panic!("No rates returned for date {date}"));
 
-                table.add_row(vec![
-                    Cell::new(date).fg(if_supports_colour(&stdout, Color::Blue)),
-                    Cell::new(first.0)
-                        .set_alignment(CellAlignment::Center)
-                        .fg(if_supports_colour(&stdout, Color::Green)),
-                    Cell::new(first.1).fg(if_supports_colour(&stdout, Color::Cyan)),
-                ]);
+                table.add_row(row(&stdout, day, Some(date), first.0, first.1));
 
                 for (k, v) in iter {
-                    table.add_row(vec![
-                        Cell::new(""),
-                        Cell::new(k)
-                            .set_alignment(CellAlignment::Center)
-                            .fg(if_supports_colour(&stdout, Color::Green)),
-                        Cell::new(v).fg(if_supports_colour(&stdout, Color::Cyan)),
-                    ]);
+                    table.add_row(row(&stdout, day, None, k, v));
                 }
             }
 
@@ -119,3 +263,15 @@ impl ExecuteSubcommand for Command {
         Ok(())
     }
 }
+
+/// Convert the colour of a series in a chart to the colour of a table cell.
+fn chart_colour(index: usize) -> Color {
+    match chart::colour(index) {
+        termcolor::Color::Green => Color::Green,
+        termcolor::Color::Yellow => Color::Yellow,
+        termcolor::Color::Magenta => Color::Magenta,
+        termcolor::Color::Blue => Color::Blue,
+        termcolor::Color::Red => Color::Red,
+        _ => Color::Cyan,
+    }
+}
@@ -0,0 +1,368 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow};
+use chrono::NaiveDate;
+use clap::Parser;
+use lib_frankfurter::{
+    Currency, ValidDate,
+    api::{self, ServerClient},
+};
+use plotters::{
+    coord::{
+        Shift,
+        ranged1d::{AsRangedCoord, ValueFormatter},
+    },
+    prelude::*,
+    style::{FontStyle, register_font},
+};
+use strum::{Display, EnumString, VariantNames};
+use termcolor::StandardStream;
+
+use super::{
+    ExecuteSubcommand,
+    chart::{Series, format_value},
+    utils::parse_path_with_format,
+};
+
+/// Name the font for text in plots is registered under, which is the default font of `plotters`.
+const FONT_FAMILY: &str = "sans-serif";
+
+/// Font to draw text with unless another is given, a subset of DejaVu Sans with the characters of
+/// Latin-1 and common currency symbols, see `assets/fonts/LICENSE`.
+const DEFAULT_FONT: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans-Latin.ttf");
+
+/// Colours of the series, in order, repeating if there are more series than colours.
+const PALETTE: [RGBColor; 6] = [
+    RGBColor(31, 119, 180),
+    RGBColor(255, 127, 14),
+    RGBColor(44, 160, 44),
+    RGBColor(214, 39, 40),
+    RGBColor(148, 103, 189),
+    RGBColor(140, 86, 75),
+];
+
+/// Render exchange rates over a given time period to an SVG or PNG file
+///
+/// The format of the file is taken from its extension, e.g.
+/// `frs plot EUR USD,GBP -s 2024-01-01 -e 2024-03-31 -o rates.svg`
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// Base currency to convert FROM
+    #[arg(ignore_case = true, index = 1, default_value_t)]
+    base: Currency,
+
+    /// Target currencies to plot, e.g. USD,AUD [default: all]
+    #[arg(ignore_case = true, index = 2, value_delimiter = ',')]
+    targets: Vec<Currency>,
+
+    /// The start date to plot exchange rates from [form: yyyy-mm-dd, alias: from]
+    #[arg(long, short = 's', alias = "from", next_line_help(true))]
+    start: ValidDate,
+    /// The end date to plot exchange rates to [form: yyyy-mm-dd, alias: to, default: today]
+    #[arg(long, short = 'e', alias = "to", next_line_help(true))]
+    end: Option<ValidDate>,
+
+    /// File to write the plot to, ending in .svg or .png
+    #[arg(long, short = 'o', value_parser = parse_path_with_format::<ImageFormat>)]
+    output: (PathBuf, ImageFormat),
+
+    /// Title of the plot [default: e.g. "EUR exchange rates, 2024-01-02 to 2024-03-28"]
+    #[arg(long, short = 't')]
+    title: Option<String>,
+
+    /// Width of the plot, in pixels
+    #[arg(long, default_value_t = 1024, value_parser = clap::value_parser!(u32).range(200..=8192))]
+    width: u32,
+
+    /// Height of the plot, in pixels
+    #[arg(long, default_value_t = 576, value_parser = clap::value_parser!(u32).range(150..=8192))]
+    height: u32,
+
+    /// Use a logarithmic scale for the rates, to compare relative changes of currencies
+    #[arg(long)]
+    log: bool,
+
+    /// Overlay the moving average of each currency over the given number of published rates, e.g. 20
+    #[arg(long, value_name = "RATES", value_parser = clap::value_parser!(u32).range(2..))]
+    moving_average: Option<u32>,
+
+    /// TrueType or OpenType font to draw text with [default: DejaVu Sans, built in]
+    #[arg(long, value_name = "FILE")]
+    font: Option<PathBuf>,
+}
+
+/// Format of the image to write, by file extension.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display, EnumString, VariantNames)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+enum ImageFormat {
+    Svg,
+    Png,
+}
+
+/// Register the font to draw text with, which is the [`DEFAULT_FONT`] unless a file is given.
+///
+/// `plotters` keeps fonts for the rest of the program, so a font read from a file is never freed.
+fn load_font(path: Option<&Path>) -> anyhow::Result<()> {
+    let bytes = match path {
+        Some(path) => fs::read(path)
+            .with_context(|| format!("Failed to read font {}", path.display()))?
+            .leak(),
+        None => DEFAULT_FONT,
+    };
+
+    register_font(FONT_FAMILY, FontStyle::Normal, bytes)
+        .map_err(|_| anyhow!("Invalid font, it must be a TrueType or OpenType font"))
+}
+
+/// Average each value with the previous values, over the given number of values, skipping gaps.
+fn moving_average(values: &[Option<f64>], window: usize) -> Vec<Option<f64>> {
+    let mut previous = Vec::with_capacity(values.len());
+
+    values
+        .iter()
+        .map(|v| {
+            let v = (*v)?;
+            previous.push(v);
+            (previous.len() >= window)
+                .then(|| previous[previous.len() - window..].iter().sum::<f64>() / window as f64)
+        })
+        .collect()
+}
+
+/// Split the values into runs of consecutive dates with values, to draw as separate lines.
+fn segments<'a>(
+    dates: &'a [NaiveDate],
+    values: &'a [Option<f64>],
+) -> impl Iterator<Item = Vec<(NaiveDate, f64)>> + 'a {
+    values
+        .split(Option::is_none)
+        .scan(0, move |start, run| {
+            let segment = dates[*start..]
+                .iter()
+                .copied()
+                .zip(run.iter().flatten().copied())
+                .collect::<Vec<_>>();
+            *start += run.len() + 1;
+            Some(segment)
+        })
+        .filter(|s| !s.is_empty())
+}
+
+impl Command {
+    /// Default title of the plot, from the base currency and dates.
+    fn default_title(&self, dates: &[NaiveDate]) -> String {
+        match (dates.first(), dates.last()) {
+            (Some(first), Some(last)) => format!("{} exchange rates, {first} to {last}", self.base),
+            _ => format!("{} exchange rates", self.base),
+        }
+    }
+
+    /// Draw the series on the drawing area, with the given range of rates and labelling them
+    /// precisely enough to tell apart rates across the given spread.
+    fn draw<DB, Y>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        dates: &[NaiveDate],
+        series: &[Series],
+        rates: Y,
+        spread: f64,
+    ) -> anyhow::Result<()>
+    where
+        DB: DrawingBackend,
+        DB::ErrorType: 'static,
+        Y: AsRangedCoord<Value = f64>,
+        Y::CoordDescType: ValueFormatter<f64>,
+    {
+        let (Some(first), Some(last)) = (dates.first(), dates.last()) else {
+            return Err(anyhow!("No exchange rates to plot"));
+        };
+        let title = self
+            .title
+            .clone()
+            .unwrap_or_else(|| self.default_title(dates));
+
+        root.fill(&WHITE)?;
+        let mut chart = ChartBuilder::on(root)
+            .caption(title, (FONT_FAMILY, 24))
+            .margin(16)
+            .x_label_area_size(40)
+            .y_label_area_size(70)
+            .build_cartesian_2d(*first..*last, rates)?;
+
+        chart
+            .configure_mesh()
+            .x_labels(8)
+            .x_label_formatter(&|d| d.format("%Y-%m-%d").to_string())
+            .y_desc(format!("Rate per 1 {}", self.base))
+            .y_label_formatter(&|v| format_value(*v, spread))
+            .draw()?;
+
+        for (i, s) in series.iter().enumerate() {
+            let colour = PALETTE[i % PALETTE.len()];
+
+            chart
+                .draw_series(
+                    segments(dates, &s.values)
+                        .flat_map(|segment| LineSeries::new(segment, colour.stroke_width(2))),
+                )?
+                .label(&s.label)
+                .legend(move |(x, y)| {
+                    PathElement::new([(x, y), (x + 20, y)], colour.stroke_width(2))
+                });
+
+            if let Some(window) = self.moving_average {
+                let average = moving_average(&s.values, window as usize);
+                let style = colour.mix(0.6).stroke_width(1);
+
+                chart
+                    .draw_series(
+                        segments(dates, &average)
+                            .flat_map(|segment| DashedLineSeries::new(segment, 6, 4, style)),
+                    )?
+                    .label(format!("{} {window}-rate moving average", s.label))
+                    .legend(move |(x, y)| PathElement::new([(x, y), (x + 20, y)], style));
+            }
+        }
+
+        chart
+            .configure_series_labels()
+            .position(SeriesLabelPosition::UpperLeft)
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .label_font((FONT_FAMILY, 14))
+            .draw()?;
+
+        root.present()?;
+        Ok(())
+    }
+
+    /// Draw the series to a drawing area of the given backend, on a linear or logarithmic scale.
+    fn render<DB>(
+        &self,
+        root: DrawingArea<DB, Shift>,
+        dates: &[NaiveDate],
+        series: &[Series],
+    ) -> anyhow::Result<()>
+    where
+        DB: DrawingBackend,
+        DB::ErrorType: 'static,
+    {
+        let (min, max) = series
+            .iter()
+            .flat_map(|s| s.values.iter().flatten().copied())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+                (min.min(v), max.max(v))
+            });
+        // Leave some space above and below the lines
+        let padding = ((max - min) * 0.05).max(max.abs() * 0.001);
+
+        if self.log {
+            self.draw(
+                &root,
+                dates,
+                series,
+                ((min - padding).max(min / 2.0)..max + padding).log_scale(),
+                max - min,
+            )
+        } else {
+            self.draw(
+                &root,
+                dates,
+                series,
+                min - padding..max + padding,
+                max - min,
+            )
+        }
+    }
+}
+
+impl ExecuteSubcommand for Command {
+    /// Executes the `plot` subcommand.
+    async fn execute(
+        self,
+        server_client: ServerClient,
+        mut stdout: StandardStream,
+    ) -> anyhow::Result<()> {
+        load_font(self.font.as_deref())?;
+
+        let response = server_client
+            .period(api::period::Request {
+                amount: None,
+                base: Some(self.base.clone()),
+                targets: Some(self.targets.clone()),
+                start_date: self.start,
+                end_date: self.end,
+            })
+            .await?;
+
+        let pivot = response.pivot();
+        let dates: Vec<NaiveDate> = pivot.rows.iter().map(|(d, _)| **d).collect();
+        let series: Vec<Series> = pivot
+            .currencies
+            .iter()
+            .enumerate()
+            .map(|(i, currency)| Series {
+                label: currency.to_string(),
+                values: pivot.rows.iter().map(|(_, v)| v[i].map(|v| *v)).collect(),
+            })
+            .collect();
+
+        let (path, format) = &self.output;
+        let size = (self.width, self.height);
+        match format {
+            ImageFormat::Svg => self.render(
+                SVGBackend::new(path, size).into_drawing_area(),
+                &dates,
+                &series,
+            )?,
+            ImageFormat::Png => self.render(
+                BitMapBackend::new(path, size).into_drawing_area(),
+                &dates,
+                &series,
+            )?,
+        }
+
+        writeln!(&mut stdout, "{}", path.display())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests_plot {
+    use super::*;
+
+    #[test]
+    fn test_moving_average() {
+        assert_eq!(
+            moving_average(&[Some(1.0), Some(3.0), None, Some(5.0), Some(10.0)], 2),
+            [None, Some(2.0), None, Some(4.0), Some(7.5)]
+        );
+        assert_eq!(moving_average(&[Some(1.0)], 2), [None]);
+    }
+
+    #[test]
+    fn test_segments() {
+        let dates: Vec<NaiveDate> = (1..=5)
+            .map(|d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap())
+            .collect();
+        let segments: Vec<_> =
+            segments(&dates, &[None, Some(1.0), Some(2.0), None, Some(3.0)]).collect();
+
+        assert_eq!(
+            segments,
+            [
+                vec![(dates[1], 1.0), (dates[2], 2.0)],
+                vec![(dates[4], 3.0)]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_font() {
+        load_font(None).unwrap();
+    }
+}
@@ -0,0 +1,416 @@
+use std::{
+    collections::HashMap,
+    io::Write,
+    net::SocketAddr,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use chrono::Utc;
+use clap::Parser;
+use lib_frankfurter::{
+    Currency, CurrencyValue, ValidDate,
+    api::{ServerClient, ServerClientRequest, convert, currencies, period},
+    calendar,
+};
+use serde::Serialize;
+use termcolor::StandardStream;
+
+use super::ExecuteSubcommand;
+
+/// Run a local caching proxy of the Frankfurter API
+///
+/// Serves the same `/v1/latest`, `/v1/{date}`, `/v1/{start}..{end}` and `/v1/currencies` routes,
+/// answering from an in-memory cache and forwarding misses to the API given by `--url`.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// Address to listen on, e.g. 0.0.0.0:8090 to accept connections from other hosts
+    #[arg(short = 'a', long, default_value = "127.0.0.1:8090")]
+    address: SocketAddr,
+
+    /// Maximum number of seconds to cache the latest rates and the currencies for
+    ///
+    /// The latest rates also expire as soon as new rates are expected to be published, while
+    /// historical rates never change and are cached until evicted.
+    #[arg(long, default_value_t = 3600)]
+    ttl: u64,
+
+    /// Maximum number of responses to cache, evicting the oldest ones first
+    #[arg(long, default_value_t = 10_000)]
+    capacity: usize,
+}
+
+/// Header indicating whether a response was served from the cache (`HIT`) or not (`MISS`).
+const CACHE_HEADER: &str = "x-cache";
+
+/// Cached body of a successful response from the upstream API.
+struct Entry {
+    body: Vec<u8>,
+    inserted: Instant,
+    /// Expiry of the entry, or [`None`] for responses which will never change.
+    expires: Option<Instant>,
+}
+
+impl Entry {
+    fn is_fresh(&self) -> bool {
+        self.expires.is_none_or(|e| Instant::now() < e)
+    }
+}
+
+/// State shared between the handlers of the server.
+struct AppState {
+    server_client: ServerClient,
+    ttl: Duration,
+    capacity: usize,
+    cache: Mutex<HashMap<String, Entry>>,
+}
+
+impl AppState {
+    /// Get the expiry for responses which change when new rates are published.
+    fn latest_expiry(&self) -> Instant {
+        let now = Utc::now();
+        let until_publication = (calendar::next_publication(now) - now)
+            .to_std()
+            .unwrap_or_default();
+
+        Instant::now() + self.ttl.min(until_publication)
+    }
+
+    /// Get the expiry for responses including rates for the given date.
+    fn expiry_for(&self, date: ValidDate) -> Option<Instant> {
+        if *date < calendar::expected_latest_date(Utc::now()) {
+            None
+        } else {
+            Some(self.latest_expiry())
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let cache = self.cache.lock().expect("Cache lock poisoned");
+        cache
+            .get(key)
+            .filter(|e| e.is_fresh())
+            .map(|e| e.body.clone())
+    }
+
+    fn insert(&self, key: String, body: Vec<u8>, expires: Option<Instant>) {
+        let mut cache = self.cache.lock().expect("Cache lock poisoned");
+        if !cache.contains_key(&key) && cache.len() >= self.capacity {
+            cache.retain(|_, e| e.is_fresh());
+        }
+        if !cache.contains_key(&key) && cache.len() >= self.capacity {
+            let oldest = cache
+                .iter()
+                .min_by_key(|(_, e)| e.inserted)
+                .map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                cache.remove(&oldest);
+            }
+        }
+
+        cache.insert(
+            key,
+            Entry {
+                body,
+                inserted: Instant::now(),
+                expires,
+            },
+        );
+    }
+
+    /// Answer the request from the cache, or fetch it from the upstream API and cache the result.
+    async fn cached<Req, Resp, Fut>(
+        &self,
+        req: Req,
+        expires: Option<Instant>,
+        fetch: impl FnOnce(Req) -> Fut,
+    ) -> Response
+    where
+        Req: ServerClientRequest,
+        Resp: Serialize,
+        Fut: Future<Output = Result<Resp, lib_frankfurter::Error>>,
+    {
+        let key = match req.setup() {
+            Ok((url, params)) => format!(
+                "{url}?{}",
+                params
+                    .iter()
+                    .map(|(k, v)| format!("{k}={v}"))
+                    .collect::<Vec<String>>()
+                    .join("&")
+            ),
+            Err(e) => return error_response(e),
+        };
+
+        if let Some(body) = self.get(&key) {
+            return json_response(body, "HIT");
+        }
+
+        match fetch(req).await.and_then(|r| Ok(serde_json::to_vec(&r)?)) {
+            Ok(body) => {
+                self.insert(key, body.clone(), expires);
+                json_response(body, "MISS")
+            }
+            Err(e) => error_response(e),
+        }
+    }
+}
+
+fn json_response(body: Vec<u8>, cache_status: &'static str) -> Response {
+    (
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            ),
+            (
+                header::HeaderName::from_static(CACHE_HEADER),
+                HeaderValue::from_static(cache_status),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+fn message_response(status: StatusCode, message: impl ToString) -> Response {
+    (
+        status,
+        Json(serde_json::json!({ "message": message.to_string() })),
+    )
+        .into_response()
+}
+
+/// Convert an error from the upstream API into a response, passing through error responses.
+fn error_response(e: lib_frankfurter::Error) -> Response {
+    if let lib_frankfurter::Error::InvalidResponse { status, body, .. } = e {
+        return (
+            StatusCode::from_u16(status.as_u16()).unwrap_or(StatusCode::BAD_GATEWAY),
+            [(header::CONTENT_TYPE, "application/json")],
+            body,
+        )
+            .into_response();
+    }
+
+    let status = if e.is_client_validation() {
+        StatusCode::UNPROCESSABLE_ENTITY
+    } else if e.is_network() {
+        StatusCode::BAD_GATEWAY
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    message_response(status, e)
+}
+
+/// Query parameters shared by the `latest`, date and time period routes.
+struct Params {
+    amount: Option<CurrencyValue>,
+    base: Option<Currency>,
+    targets: Option<Vec<Currency>>,
+}
+
+impl Params {
+    /// Parse the query parameters, accepting `from` and `to` as aliases of `base` and `symbols`.
+    fn parse(query: &HashMap<String, String>) -> Result<Self, (StatusCode, String)> {
+        // Unknown currencies get the same response as from the API
+        let not_found = |_| (StatusCode::NOT_FOUND, String::from("not found"));
+        let param = |a: &str, b: &str| query.get(a).or_else(|| query.get(b));
+
+        Ok(Self {
+            // Forwarded with as many decimal places as it was sent with, up to the maximum precision,
+            // so the upstream query and the cache key match the request
+            amount: query
+                .get("amount")
+                .map(|a| {
+                    let decimals = a.split_once('.').map_or(0, |(_, fraction)| fraction.len());
+                    CurrencyValue::parse(
+                        a,
+                        u8::try_from(decimals)
+                            .unwrap_or(u8::MAX)
+                            .min(CurrencyValue::MAX_PRECISION),
+                    )
+                })
+                .transpose()
+                .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?,
+            base: param("base", "from")
+                .map(|b| Currency::from_str(b))
+                .transpose()
+                .map_err(not_found)?,
+            targets: param("symbols", "to")
+                .map(|t| t.split(',').map(Currency::from_str).collect())
+                .transpose()
+                .map_err(not_found)?,
+        })
+    }
+}
+
+async fn root() -> Response {
+    Json(serde_json::json!({ "docs": "https://frankfurter.dev" })).into_response()
+}
+
+async fn latest(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Response {
+    let Params {
+        amount,
+        base,
+        targets,
+    } = match Params::parse(&query) {
+        Ok(p) => p,
+        Err((status, message)) => return message_response(status, message),
+    };
+    let req = convert::Request {
+        amount,
+        base,
+        targets,
+        date: None,
+    };
+
+    let expires = Some(state.latest_expiry());
+    state
+        .cached(req, expires, |req| state.server_client.convert(req))
+        .await
+}
+
+async fn date_or_period(
+    State(state): State<Arc<AppState>>,
+    Path(path): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Response {
+    let Params {
+        amount,
+        base,
+        targets,
+    } = match Params::parse(&query) {
+        Ok(p) => p,
+        Err((status, message)) => return message_response(status, message),
+    };
+    let invalid_date = |e| message_response(StatusCode::UNPROCESSABLE_ENTITY, e);
+
+    match path.split_once("..") {
+        Some((start, end)) => {
+            let start_date = match ValidDate::from_str(start) {
+                Ok(d) => d,
+                Err(e) => return invalid_date(e),
+            };
+            let end_date = match Some(end).filter(|e| !e.is_empty()).map(ValidDate::from_str) {
+                Some(Err(e)) => return invalid_date(e),
+                end => end.and_then(Result::ok),
+            };
+            let req = period::Request {
+                amount,
+                base,
+                targets,
+                start_date,
+                end_date,
+            };
+
+            let expires = state.expiry_for(end_date.unwrap_or_else(ValidDate::max));
+            state
+                .cached(req, expires, |req| state.server_client.period(req))
+                .await
+        }
+        None => {
+            let date = match ValidDate::from_str(&path) {
+                Ok(d) => d,
+                Err(e) => return invalid_date(e),
+            };
+            let req = convert::Request {
+                amount,
+                base,
+                targets,
+                date: Some(date),
+            };
+
+            let expires = state.expiry_for(date);
+            state
+                .cached(req, expires, |req| state.server_client.convert(req))
+                .await
+        }
+    }
+}
+
+async fn currencies(State(state): State<Arc<AppState>>) -> Response {
+    let expires = Some(Instant::now() + state.ttl);
+    state
+        .cached(currencies::Request::default(), expires, |req| {
+            state.server_client.currencies(req)
+        })
+        .await
+}
+
+/// Serve the router on the given address until interrupted, printing the address once listening.
+pub(super) async fn listen(
+    address: SocketAddr,
+    router: Router,
+    stdout: &mut StandardStream,
+) -> anyhow::Result<()> {
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    writeln!(stdout, "Listening on http://{}", listener.local_addr()?)?;
+    stdout.flush()?;
+
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await?;
+
+    Ok(())
+}
+
+impl ExecuteSubcommand for Command {
+    /// Executes the `serve` subcommand.
+    async fn execute(
+        self,
+        server_client: ServerClient,
+        mut stdout: StandardStream,
+    ) -> anyhow::Result<()> {
+        let state = Arc::new(AppState {
+            server_client,
+            ttl: Duration::from_secs(self.ttl),
+            capacity: self.capacity.max(1),
+            cache: Mutex::new(HashMap::new()),
+        });
+
+        let router = Router::new()
+            .route("/", get(root))
+            .route("/v1", get(root))
+            .route("/v1/", get(root))
+            .route("/v1/latest", get(latest))
+            .route("/v1/currencies", get(currencies))
+            .route("/v1/{date}", get(date_or_period))
+            .with_state(state);
+
+        listen(self.address, router, &mut stdout).await
+    }
+}
+
+#[cfg(test)]
+mod tests_serve {
+    use super::*;
+
+    #[test]
+    fn test_params_amount() {
+        let amount = |a: &str| {
+            Params::parse(&HashMap::from([(String::from("amount"), String::from(a))]))
+                .map(|p| p.amount.unwrap().to_string())
+        };
+
+        assert_eq!(amount("10").unwrap(), "10");
+        assert_eq!(amount("10.50").unwrap(), "10.50");
+        assert_eq!(amount("0.001").unwrap(), "0.001");
+        assert_eq!(amount("1.123456789").unwrap(), "1.12345679");
+        assert!(amount("0.0001").is_ok());
+        assert!(amount("0").is_err());
+        assert!(amount("abc").is_err());
+    }
+}
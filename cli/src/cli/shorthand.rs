@@ -0,0 +1,318 @@
+//! Free-form conversion queries, e.g. `frs 100 usd to eur` or `frs 2.5k gbp in jpy on 2023-05-01`,
+//! which are parsed into the arguments of the `convert` subcommand.
+
+use clap::{Arg, CommandFactory, Parser, error::ErrorKind};
+use lib_frankfurter::Currency;
+
+use super::{BIN_NAME, Cli, convert};
+
+/// Words which may separate the base currency from the target currencies.
+const CONNECTORS: [&str; 4] = ["to", "in", "into", "as"];
+
+/// Powers of ten which amounts are multiplied by for their suffix, e.g. `2.5k`.
+const SUFFIXES: [(&str, usize); 4] = [("k", 3), ("m", 6), ("bn", 9), ("b", 9)];
+
+/// Parse an amount, which may use `,` or `_` to separate thousands and end in a suffix, into a
+/// decimal number.
+///
+/// Suffixes move the decimal point of the number as written rather than multiplying it as a float,
+/// so that e.g. `1.1k` is exactly `1100`.
+fn parse_amount(word: &str) -> Option<String> {
+    let word = word.replace([',', '_'], "");
+    let (number, exponent) = SUFFIXES
+        .iter()
+        .find_map(|(suffix, exponent)| Some((word.strip_suffix(suffix)?, *exponent)))
+        .unwrap_or((&word, 0));
+    let (sign, number) = match number.strip_prefix('-') {
+        Some(number) => ("-", number),
+        None => ("", number),
+    };
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if integer.is_empty() && fraction.is_empty()
+        || !integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let digits = format!("{integer}{fraction:0<exponent$}");
+    let (integer, fraction) = digits.split_at(integer.len() + exponent);
+    let integer = match integer.trim_start_matches('0') {
+        "" => "0",
+        integer => integer,
+    };
+
+    Some(match fraction.trim_end_matches('0') {
+        "" => format!("{sign}{integer}"),
+        fraction => format!("{sign}{integer}.{fraction}"),
+    })
+}
+
+/// Parse the words of a query into the arguments of the `convert` subcommand.
+///
+/// Queries are of the form `[AMOUNT] BASE [to|in] [TARGETS...] [on DATE]`, where the amount may
+/// also follow the base and targets may be separated by spaces, commas or `and`.
+fn parse_query(words: &[String]) -> Result<Vec<String>, String> {
+    let words: Vec<String> = words
+        .iter()
+        .flat_map(|w| w.split(','))
+        .filter(|w| !w.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect();
+    let mut words = words.iter().map(String::as_str).peekable();
+
+    let mut amount = words.next_if(|w| parse_amount(w).is_some());
+    let base = match words.next() {
+        Some(word) => word
+            .parse::<Currency>()
+            .map_err(|_| format!("expected a currency, found '{word}'"))?,
+        None => return Err(String::from("expected a currency after the amount")),
+    };
+    if amount.is_none() {
+        amount = words.next_if(|w| parse_amount(w).is_some());
+    }
+    let connector = words.next_if(|w| CONNECTORS.contains(w));
+
+    let mut targets = Vec::new();
+    let mut date = None;
+    while let Some(word) = words.next() {
+        match word {
+            "and" if !targets.is_empty() => {}
+            "on" | "at" => match (words.next(), date) {
+                (Some(d), None) => date = Some(d),
+                (_, Some(_)) => return Err(String::from("the date was given more than once")),
+                (None, _) => return Err(format!("expected a date after '{word}'")),
+            },
+            _ => targets.push(
+                word.parse::<Currency>()
+                    .map_err(|_| format!("expected a currency, found '{word}'"))?,
+            ),
+        }
+    }
+
+    if let (Some(connector), true) = (connector, targets.is_empty()) {
+        return Err(format!("expected a currency after '{connector}'"));
+    }
+
+    let mut args = vec![String::from("convert"), base.to_string()];
+    if !targets.is_empty() {
+        args.push(
+            targets
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+    if let Some(amount) = amount.and_then(parse_amount) {
+        args.extend([String::from("--amount"), amount]);
+    }
+    if let Some(date) = date {
+        args.extend([String::from("--date"), date.to_string()]);
+    }
+
+    Ok(args)
+}
+
+/// Find the argument of a command matching an option, e.g. `--url`, `--url=URL` or `-u`.
+fn find_arg<'a>(command: &'a clap::Command, option: &str) -> Option<&'a Arg> {
+    let name = option.split_once('=').map_or(option, |(name, _)| name);
+    command
+        .get_arguments()
+        .find(|arg| match name.strip_prefix("--") {
+            Some(long) => arg.get_long() == Some(long),
+            None => name
+                .strip_prefix('-')
+                .and_then(|short| short.chars().next())
+                .is_some_and(|short| arg.get_short() == Some(short)),
+        })
+}
+
+/// Split the options after a query into those of [`Cli`], e.g. `--url`, and the rest, which are
+/// left for the `convert` subcommand, including options of both, e.g. `-d` for `--date`.
+fn split_options(options: &[String]) -> (Vec<String>, Vec<String>) {
+    let (cli, convert) = (<Cli>::command(), convert::Command::command());
+    let (mut global, mut rest) = (Vec::new(), Vec::new());
+
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        match find_arg(&cli, option) {
+            Some(arg) if find_arg(&convert, option).is_none() => {
+                global.push(option.clone());
+                // The value of the option may be the next word, e.g. `--url URL`
+                if arg.get_action().takes_values() && !option.contains('=') {
+                    global.extend(options.next().cloned());
+                }
+            }
+            _ => rest.push(option.clone()),
+        }
+    }
+
+    (global, rest)
+}
+
+/// Free-form conversion query, i.e. the words at the end of the command line from the query on,
+/// including any options after it, e.g. `100 usd to eur --json`.
+#[derive(Debug)]
+pub(super) struct Shorthand(pub(super) Vec<String>);
+
+impl Shorthand {
+    /// Parse the query, followed by any options of the `convert` subcommand, e.g. `--json`, or of
+    /// [`Cli`], e.g. `--url`, exiting with a usage error if it is invalid.
+    ///
+    /// The command line is parsed again with the query replaced by the arguments of `convert`,
+    /// given the arguments before the query, i.e. the binary and any options of [`Cli`].
+    pub(super) fn parse(self, prefix: &[String]) -> Cli {
+        let words = self.0;
+        // Options start at the first word which isn't a negative number
+        let split = words
+            .iter()
+            .position(|w| w.starts_with('-') && parse_amount(w).is_none())
+            .unwrap_or(words.len());
+        let (query, options) = words.split_at(split);
+
+        let args = match parse_query(query) {
+            Ok(args) => args,
+            // Not a query at all, so most likely a mistyped subcommand
+            Err(_)
+                if query.first().is_some_and(|w| parse_amount(w).is_none()) && query.len() == 1 =>
+            {
+                <Cli>::command()
+                    .bin_name(BIN_NAME)
+                    .error(
+                        ErrorKind::InvalidSubcommand,
+                        format!("unrecognized subcommand or currency '{}'", query[0]),
+                    )
+                    .exit()
+            }
+            Err(e) => <Cli>::command()
+                .bin_name(BIN_NAME)
+                .error(
+                    ErrorKind::InvalidValue,
+                    format!("invalid query '{}': {e}", query.join(" ")),
+                )
+                .exit(),
+        };
+
+        let (global, options) = split_options(options);
+
+        Cli::try_parse_from(
+            prefix
+                .iter()
+                .cloned()
+                .chain(global)
+                .chain(args)
+                .chain(options),
+        )
+        .unwrap_or_else(|e| e.exit())
+    }
+}
+
+#[cfg(test)]
+mod tests_shorthand {
+    use super::*;
+    use crate::cli::Command;
+
+    fn query(s: &str) -> Result<String, String> {
+        let words: Vec<String> = s.split(' ').map(String::from).collect();
+        parse_query(&words).map(|args| args.join(" "))
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        let amount = |s: &str| parse_amount(s);
+        assert_eq!(amount("100").as_deref(), Some("100"));
+        assert_eq!(amount("2.5k").as_deref(), Some("2500"));
+        assert_eq!(amount("1.1k").as_deref(), Some("1100"));
+        assert_eq!(amount("1,234.5").as_deref(), Some("1234.5"));
+        assert_eq!(amount("3m").as_deref(), Some("3000000"));
+        assert_eq!(amount("1bn").as_deref(), Some("1000000000"));
+        assert_eq!(amount("0.0015k").as_deref(), Some("1.5"));
+        assert_eq!(amount("1.23456k").as_deref(), Some("1234.56"));
+        assert_eq!(amount("007.50").as_deref(), Some("7.5"));
+        assert_eq!(amount(".5").as_deref(), Some("0.5"));
+        assert_eq!(amount("-5").as_deref(), Some("-5"));
+        assert_eq!(amount("usd"), None);
+        assert_eq!(amount("inf"), None);
+        assert_eq!(amount("k"), None);
+        assert_eq!(amount("1.2.3"), None);
+    }
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(
+            query("100 usd to eur").unwrap(),
+            "convert USD EUR --amount 100"
+        );
+        assert_eq!(
+            query("2.5k GBP in jpy on 2023-05-01").unwrap(),
+            "convert GBP JPY --amount 2500 --date 2023-05-01"
+        );
+        assert_eq!(query("usd eur").unwrap(), "convert USD EUR");
+        assert_eq!(
+            query("usd 10 to eur, gbp and chf").unwrap(),
+            "convert USD EUR,GBP,CHF --amount 10"
+        );
+        assert_eq!(query("eur").unwrap(), "convert EUR");
+    }
+
+    #[test]
+    fn test_split_options() {
+        let options = |s: &str| s.split(' ').map(String::from).collect::<Vec<_>>();
+        let (global, rest) = split_options(&options(
+            "--json --url http://localhost:8080 -d 2024-01-02 --debug",
+        ));
+
+        assert_eq!(global, options("--url http://localhost:8080 --debug"));
+        // `-d` is `--date` for `convert`
+        assert_eq!(rest, options("--json -d 2024-01-02"));
+        assert_eq!(
+            split_options(&options("--color=never -r")),
+            (options("--color=never"), options("-r"))
+        );
+    }
+
+    #[test]
+    fn test_parse_query_invalid() {
+        for s in [
+            "100",
+            "100 dollars to eur",
+            "usd to",
+            "usd to eur on",
+            "usd eur on 2023-05-01 on 2023-05-02",
+            "and usd",
+        ] {
+            assert!(query(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = |s: &str| s.split(' ').map(String::from).collect::<Vec<_>>();
+
+        // Options of `Cli` before and after the query, given the command line explicitly
+        let cli = Cli::parse_args(args(
+            "frs --debug 1.1k usd to eur --json --url http://localhost:1",
+        ));
+        assert!(cli.debug);
+        assert_eq!(cli.url.unwrap().as_str(), "http://localhost:1/");
+        let Command::Convert(command) = cli.command else {
+            panic!("expected the convert subcommand");
+        };
+        assert_eq!(
+            format!("{command:?}"),
+            format!(
+                "{:?}",
+                convert::Command::parse_from(args("convert USD EUR --amount 1100 --json"))
+            )
+        );
+
+        // Subcommands aren't queries
+        assert!(matches!(
+            Cli::parse_args(args("frs --debug currencies")).command,
+            Command::Currencies(_)
+        ));
+    }
+}
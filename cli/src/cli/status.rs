@@ -0,0 +1,114 @@
+use std::io::Write;
+
+use anyhow::bail;
+use clap::Parser;
+use comfy_table::{
+    Cell, CellAlignment, Color, ContentArrangement, Table, modifiers::UTF8_ROUND_CORNERS,
+    presets::UTF8_FULL_CONDENSED,
+};
+use lib_frankfurter::api::{ServerClient, health::Health};
+use termcolor::StandardStream;
+
+use super::{ExecuteSubcommand, SubcommandBaseModifiers, utils::if_supports_colour};
+
+/// Check the health of the API, including whether it is up to date with the latest rates
+///
+/// Exits with a non-zero status code if the API is unreachable, returns an error or is stale.
+#[derive(Debug, Parser)]
+pub struct Command {
+    #[command(flatten)]
+    modifiers: SubcommandBaseModifiers,
+}
+
+/// Get the rows to display for the given [`Health`], along with whether each value is healthy.
+fn rows(health: &Health) -> Vec<(&'static str, String, bool)> {
+    let yes_no = |b: bool| if b { "yes" } else { "no" }.to_owned();
+    let or_unknown = |o: Option<String>| o.unwrap_or_else(|| String::from("unknown"));
+
+    vec![
+        ("URL", health.url.to_string(), true),
+        ("Reachable", yes_no(health.reachable), health.reachable),
+        (
+            "Latency",
+            format!("{} ms", health.latency.as_millis()),
+            true,
+        ),
+        ("API version", or_unknown(health.api_version.clone()), true),
+        (
+            "Latest date",
+            or_unknown(health.latest_date.map(|d| d.to_string())),
+            !health.is_stale(),
+        ),
+        ("Expected date", health.expected_date.to_string(), true),
+        ("Stale", yes_no(health.is_stale()), !health.is_stale()),
+    ]
+}
+
+impl ExecuteSubcommand for Command {
+    /// Executes the `status` subcommand.
+    async fn execute(
+        self,
+        server_client: ServerClient,
+        mut stdout: StandardStream,
+    ) -> anyhow::Result<()> {
+        let SubcommandBaseModifiers { json, raw } = self.modifiers;
+        let health = server_client.health().await;
+
+        if json {
+            let value = serde_json::json!({
+                "url": health.url,
+                "reachable": health.reachable,
+                "latency_ms": health.latency.as_millis() as u64,
+                "api_version": health.api_version,
+                "latest_date": health.latest_date,
+                "expected_date": health.expected_date,
+                "stale": health.is_stale(),
+                "error": health.error.as_ref().map(ToString::to_string),
+            });
+            writeln!(&mut stdout, "{}", serde_json::to_string_pretty(&value)?)?;
+        } else if raw {
+            stdout.write_all(
+                &rows(&health)
+                    .into_iter()
+                    .flat_map(|(k, v, _)| format!("{k}\t{v}\r\n").into_bytes())
+                    .collect::<Vec<u8>>(),
+            )?;
+        } else {
+            let mut table = Table::new();
+            table
+                .load_preset(UTF8_FULL_CONDENSED)
+                .apply_modifier(UTF8_ROUND_CORNERS)
+                .set_header(vec!["Check", "Value"])
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .add_rows(rows(&health).into_iter().map(|(k, v, healthy)| {
+                    vec![
+                        Cell::new(k)
+                            .set_alignment(CellAlignment::Right)
+                            .fg(if_supports_colour(&stdout, Color::Green)),
+                        Cell::new(v).fg(if_supports_colour(
+                            &stdout,
+                            if healthy { Color::Cyan } else { Color::Red },
+                        )),
+                    ]
+                }));
+
+            writeln!(&mut stdout, "{table}")?;
+        }
+
+        if let Some(e) = health.error {
+            return Err(e.into());
+        }
+        if health.is_stale() {
+            bail!(
+                "The latest rates ({}) are older than expected ({})",
+                health
+                    .latest_date
+                    .map(|d| d.to_string())
+                    .unwrap_or_default(),
+                health.expected_date
+            );
+        }
+
+        Ok(())
+    }
+}
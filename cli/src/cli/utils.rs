@@ -1,5 +1,48 @@
+use std::{path::PathBuf, str::FromStr};
+
+use lib_frankfurter::CurrencyValue;
+use strum::VariantNames;
 use termcolor::{StandardStream, WriteColor};
 
+/// Parse an amount at the maximum precision, to be limited to the `--precision` argument once all
+/// arguments have been parsed, see [`CurrencyValue::with_precision`].
+pub fn parse_amount(s: &str) -> Result<CurrencyValue, lib_frankfurter::Error> {
+    CurrencyValue::parse(s, CurrencyValue::MAX_PRECISION)
+}
+
+/// Parse the path of a file, along with its format given by its extension, for formats whose
+/// variants are their extensions, e.g. `csv`.
+pub fn parse_path_with_format<F: FromStr + VariantNames>(s: &str) -> Result<(PathBuf, F), String> {
+    let path = PathBuf::from(s);
+    let format = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(|e| e.parse().ok())
+        .ok_or_else(|| {
+            format!(
+                "file must end in one of: {}",
+                F::VARIANTS
+                    .iter()
+                    .map(|v| format!(".{v}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?;
+
+    Ok((path, format))
+}
+
+/// Width of the terminal in columns, falling back to the `COLUMNS` environment variable when stdout
+/// isn't a terminal, and then to 80 columns.
+pub fn terminal_width() -> usize {
+    // A table without a set width takes the size of the terminal when stdout is one
+    comfy_table::Table::new()
+        .width()
+        .map(usize::from)
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+        .unwrap_or(80)
+}
+
 pub fn if_supports_colour(
     stream: &StandardStream,
     colour: comfy_table::Color,
@@ -14,10 +57,35 @@ pub fn if_supports_colour(
 #[cfg(test)]
 mod tests {
     use comfy_table::Color;
+    use strum::EnumString;
     use termcolor::{ColorChoice, StandardStream};
 
     use super::*;
 
+    #[derive(PartialEq, Debug, EnumString, VariantNames)]
+    #[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+    enum ImageFormat {
+        Svg,
+        Png,
+    }
+
+    #[test]
+    fn test_parse_path_with_format() {
+        assert_eq!(
+            parse_path_with_format("rates.SVG").unwrap(),
+            (PathBuf::from("rates.SVG"), ImageFormat::Svg)
+        );
+        assert_eq!(
+            parse_path_with_format("out/rates.png").unwrap(),
+            (PathBuf::from("out/rates.png"), ImageFormat::Png)
+        );
+        assert_eq!(
+            parse_path_with_format::<ImageFormat>("rates.jpg").unwrap_err(),
+            "file must end in one of: .svg, .png"
+        );
+        assert!(parse_path_with_format::<ImageFormat>("rates").is_err());
+    }
+
     #[test]
     fn test_if_supports_colour() {
         let get_stream = StandardStream::stdout;
@@ -1,9 +1,8 @@
 mod cli;
 
-use clap::Parser;
 use cli::Cli;
 
 #[tokio::main]
 async fn main() {
-    Cli::parse().execute().await
+    Cli::parse_args(std::env::args()).execute().await
 }
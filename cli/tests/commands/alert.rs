@@ -0,0 +1,79 @@
+use predicates::{prelude::PredicateBooleanExt, str::contains};
+
+use crate::shared::get_cmd;
+
+pub(super) const COMMAND: &str = "alert";
+
+// SUCCESS CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_not_fired() {
+    get_cmd()
+        .args([COMMAND, "--move", "EUR/USD=1000", "--above", "eur/gbp=1000"])
+        .assert()
+        .success()
+        .stdout(
+            contains("EUR/USD daily move over 1000%")
+                .and(contains("EUR/GBP above 1000"))
+                .and(contains("yes").not()),
+        );
+}
+
+#[test]
+fn test_fired() {
+    get_cmd()
+        .args([
+            COMMAND,
+            "--move",
+            "EUR/USD=0.000001",
+            "--below",
+            "EUR/USD=1000",
+        ])
+        .assert()
+        .failure()
+        .code(6)
+        .stdout(contains("yes"))
+        .stderr(contains("1 alert fired"));
+
+    // Only fired alerts are printed
+    get_cmd()
+        .args([
+            COMMAND,
+            "--raw",
+            "--move",
+            "EUR/USD=0.000001",
+            "--below",
+            "EUR/USD=1000",
+        ])
+        .assert()
+        .code(6)
+        .stdout(contains("EUR/USD daily move over 0.000001%:").and(contains("below").not()));
+
+    get_cmd()
+        .args([COMMAND, "--json", "--move", "EUR/USD=0.000001"])
+        .assert()
+        .code(6)
+        .stdout(contains("\"fired\": true"));
+}
+
+// FAILURE CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_fail_no_rules() {
+    get_cmd().arg(COMMAND).assert().failure().code(2);
+}
+
+#[test]
+fn test_fail_invalid_rules() {
+    for rule in ["EUR/USD", "EUR=1", "EUR/EUR=1", "EUR/USD=-1", "EUR/USD=abc"] {
+        get_cmd()
+            .args([COMMAND, "--above", rule])
+            .assert()
+            .failure()
+            .code(2)
+            .stderr(contains("Invalid alert rule"));
+    }
+    get_cmd()
+        .args([COMMAND, "--high", "EUR/USD=1.5"])
+        .assert()
+        .failure()
+        .code(2);
+}
@@ -0,0 +1,70 @@
+use predicates::str::is_match;
+use predicates::{prelude::PredicateBooleanExt, str::contains};
+
+use crate::shared::get_cmd;
+
+pub(super) const COMMAND: &str = "calc";
+
+// SUCCESS CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_basic() {
+    get_cmd()
+        .args([
+            COMMAND,
+            "120 USD + 45.50 gbp - 10 CHF in EUR",
+            "-d",
+            "2024-01-09",
+        ])
+        .assert()
+        .success()
+        .stdout(
+            is_match("Amount +┆ Rate +┆ EUR")
+                .unwrap()
+                .and(is_match("120.00 USD ┆ [\\d.]+ +┆ +[\\d.]+").unwrap())
+                .and(is_match(" 45.50 GBP ┆ [\\d.]+ +┆ +[\\d.]+").unwrap())
+                .and(is_match("Total ┆ 2024-01-09 ┆ +[\\d.]+").unwrap()),
+        );
+}
+
+#[test]
+fn test_raw() {
+    // Defaults to the currency of the first amount
+    get_cmd()
+        .args([COMMAND, "-(10 EUR - 40 EUR) / 4 + 2 * 1.25 EUR", "--raw"])
+        .assert()
+        .success()
+        .stdout("10.00\r\n");
+}
+
+#[test]
+fn test_json() {
+    get_cmd()
+        .args([COMMAND, "(100 USD + 50 JPY) / 2", "--json"])
+        .assert()
+        .success()
+        .stdout(
+            contains("\"target\": \"USD\"")
+                .and(contains("\"currency\": \"JPY\""))
+                .and(contains("\"total\"")),
+        );
+}
+
+// FAILURE CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_fail_invalid_expression() {
+    for expression in [
+        "120 USD +",
+        "120 US",
+        "1 USD + 2",
+        "(1 USD",
+        "1 USD in",
+        "1 USD / 0",
+    ] {
+        get_cmd()
+            .args([COMMAND, expression])
+            .assert()
+            .failure()
+            .code(2)
+            .stderr(contains("Invalid calculation"));
+    }
+}
@@ -122,7 +122,155 @@ fn test_amount_ignore_separators() {
     }
 }
 
+#[test]
+fn test_amount_precision() {
+    for (amount, precision) in [("0.005", "3"), ("1234.5678", "4"), ("0.00012345", "8")] {
+        get_cmd()
+            .args([COMMAND, "-a", amount, "--precision", precision, "--json"])
+            .assert()
+            .stdout(contains(format!("\"amount\": {amount}")))
+            .success();
+    }
+
+    // Rounded to the default precision
+    get_cmd()
+        .args([COMMAND, "-a", "1234.5678", "--json"])
+        .assert()
+        .stdout(contains("\"amount\": 1234.57"))
+        .success();
+}
+
+#[test]
+fn test_quotation() {
+    let rate = |args: &[&str]| -> f64 {
+        let output = get_cmd()
+            .args([COMMAND, "EUR", "USD", "--raw"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap().trim()[4..]
+            .parse()
+            .unwrap()
+    };
+
+    // Quoted rates aren't rounded to the precision of the exchanged values
+    let direct = rate(&["--per", "1"]);
+    assert!((rate(&[]) - direct).abs() < 0.01);
+    assert!((rate(&["--per", "100"]) - direct * 100.0).abs() < 1e-6);
+    assert!((rate(&["--inverse"]) - 1.0 / direct).abs() < 1e-6);
+    assert!((rate(&["--inverse", "--per", "100"]) - 100.0 / direct).abs() < 1e-6);
+
+    get_cmd()
+        .args([COMMAND, "EUR", "JPY", "-i", "--per", "100", "--json"])
+        .assert()
+        .stdout(contains("\"mode\": \"indirect\"").and(contains("\"per\": 100")))
+        .success();
+    get_cmd()
+        .args([COMMAND, "EUR", "JPY", "-i", "--per", "100"])
+        .assert()
+        .stdout(contains("EUR per 100 JPY"))
+        .success();
+}
+
+#[test]
+fn test_format() {
+    get_cmd()
+        .args([COMMAND, "EUR", "USD,GBP", "-d", "2024-01-02", "-f", "csv"])
+        .assert()
+        .stdout(
+            is_match(
+                "^date,base,currency,value\n2024-01-02,EUR,GBP,[\\d.]+\n2024-01-02,EUR,USD,[\\d.]+\n$",
+            )
+            .unwrap(),
+        )
+        .success();
+
+    // Quoted rates
+    get_cmd()
+        .args([COMMAND, "EUR", "JPY", "-i", "--per", "100", "-f", "tsv"])
+        .assert()
+        .stdout(
+            is_match("^date\tbase\tcurrency\trate\tquote\n[\\d-]+\tEUR\tJPY\t[\\d.]+\tEUR per 100 JPY\n$")
+                .unwrap(),
+        )
+        .success();
+}
+
+#[test]
+fn test_prices() {
+    get_cmd()
+        .args([
+            COMMAND,
+            "EUR",
+            "USD",
+            "-d",
+            "2024-01-09",
+            "--prices",
+            "beancount",
+        ])
+        .assert()
+        .stdout(is_match("^2024-01-09 price USD 0\\.\\d{6} EUR\n$").unwrap())
+        .success();
+}
+
 // FAILURE CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_fail_prices_invalid_commodity() {
+    // Beancount commodities can't be symbols
+    get_cmd()
+        .args([
+            COMMAND,
+            "EUR",
+            "USD",
+            "--prices",
+            "beancount",
+            "--commodity",
+            "USD=$",
+        ])
+        .assert()
+        .failure()
+        .code(2);
+}
+
+#[test]
+fn test_fail_quotation_with_amount() {
+    for args in [&["-i", "-a", "10"][..], &["--per", "100", "-a", "10"]] {
+        get_cmd().arg(COMMAND).args(args).assert().failure().code(2);
+    }
+    get_cmd()
+        .args([COMMAND, "--per", "0"])
+        .assert()
+        .failure()
+        .code(2);
+}
+
+#[test]
+fn test_fail_amount_below_precision() {
+    for (amount, precision) in [("0.005", "2"), ("0.5", "0"), ("0.000000001", "8")] {
+        get_cmd()
+            .args([COMMAND, "-a", amount, "--precision", precision])
+            .assert()
+            .failure()
+            .code(2);
+    }
+    get_cmd()
+        .args([COMMAND, "--precision", "9"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_fail_gnucash_quotation() {
+    for arg in ["--inverse", "--per=100"] {
+        get_cmd()
+            .args([COMMAND, "EUR", "USD", arg, "--prices", "gnucash"])
+            .assert()
+            .failure()
+            .code(2);
+    }
+}
+
 #[test]
 fn test_fail_invalid_date() {
     for date in INVALID_DATES {
@@ -0,0 +1,186 @@
+use std::{fs, path::PathBuf};
+
+use predicates::{
+    prelude::PredicateBooleanExt,
+    str::{contains, is_match},
+};
+
+use crate::shared::get_cmd;
+
+pub(super) const COMMAND: &str = "convert-file";
+
+const CSV: &str = "date,description,amount,ccy
+2024-01-09,Hotel,\"1,200.50\",USD
+2024-01-09,Taxi,-45.00,gbp
+2024-01-06,Lunch,20,EUR
+";
+
+/// Write the contents to a file in the temporary directory, unique to this test run.
+fn input(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("frs-convert-file-{}-{name}", std::process::id()));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+// SUCCESS CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_csv() {
+    let path = input("transactions.csv", CSV);
+
+    get_cmd()
+        .arg(COMMAND)
+        .arg(&path)
+        .args([
+            "--amount-col",
+            "amount",
+            "--currency-col",
+            "ccy",
+            "--date-col",
+            "date",
+        ])
+        .args(["--to", "EUR"])
+        .assert()
+        .success()
+        .stdout(
+            is_match(
+                "^date,description,amount,ccy,amount_eur,rate,rate_date
+2024-01-09,Hotel,\"1,200.50\",USD,\\d+\\.\\d{2},[\\d.]+,2024-01-09
+2024-01-09,Taxi,-45.00,gbp,-\\d+\\.\\d{2},[\\d.]+,2024-01-09
+2024-01-06,Lunch,20,EUR,20.00,1,2024-01-06
+$",
+            )
+            .unwrap(),
+        );
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_json_to_file() {
+    let path = input(
+        "transactions.json",
+        r#"[{"date": "09/01/2024", "amount": 100, "ccy": "USD"}, {"date": "09/01/2024", "amount": "50.5", "ccy": "usd"}]"#,
+    );
+    let output = path.with_extension("out.json");
+
+    get_cmd()
+        .arg(COMMAND)
+        .arg(&path)
+        .args([
+            "--amount-col",
+            "amount",
+            "--currency-col",
+            "ccy",
+            "--date-col",
+            "date",
+        ])
+        .args(["--date-format", "%d/%m/%Y", "--converted-col", "eur", "-o"])
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout("");
+
+    let converted = fs::read_to_string(&output).unwrap();
+    fs::remove_file(path).unwrap();
+    fs::remove_file(output).unwrap();
+    assert!(converted.contains(r#""amount": "50.5""#));
+    assert_eq!(converted.matches(r#""eur": "#).count(), 2);
+    assert_eq!(converted.matches(r#""rate_date": "2024-01-09""#).count(), 2);
+}
+
+#[test]
+fn test_decimal_comma() {
+    let path = input(
+        "decimal-comma.csv",
+        "amount,ccy\n\"1.200,50\",EUR\n\"-12,5\",EUR\n",
+    );
+
+    get_cmd()
+        .arg(COMMAND)
+        .arg(&path)
+        .args(["--amount-col", "amount", "--currency-col", "ccy"])
+        .args(["--decimal-separator", ","])
+        .assert()
+        .success()
+        .stdout(contains("\"1.200,50\",EUR,1200.50,").and(contains("\"-12,5\",EUR,-12.50,")));
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_latest_rate_date() {
+    let path = input("latest.csv", "amount,ccy\n10,USD\n20,EUR\n");
+
+    let output = get_cmd()
+        .arg(COMMAND)
+        .arg(&path)
+        .args(["--amount-col", "amount", "--currency-col", "ccy"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    fs::remove_file(path).unwrap();
+
+    // Rows already in the target currency are dated like the latest rates of the other rows
+    let dates: Vec<String> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .skip(1)
+        .map(|line| line.rsplit(',').next().unwrap().to_owned())
+        .collect();
+    assert_eq!(dates.len(), 2);
+    assert_eq!(dates[0], dates[1]);
+}
+
+// FAILURE CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_fail_invalid_records() {
+    let path = input("invalid.csv", CSV);
+
+    get_cmd()
+        .arg(COMMAND)
+        .arg(&path)
+        .args(["--amount-col", "amt", "--currency-col", "ccy"])
+        .assert()
+        .failure()
+        .stderr(contains("Column 'amt' not found"));
+    get_cmd()
+        .arg(COMMAND)
+        .arg(&path)
+        .args(["--amount-col", "description", "--currency-col", "ccy"])
+        .assert()
+        .failure()
+        .stderr(contains("line 2").and(contains("invalid amount 'Hotel'")));
+    fs::remove_file(path).unwrap();
+
+    // Decimal commas aren't read as thousands separators
+    let path = input("decimal-comma.csv", "amount,ccy\n\"12,50\",USD\n");
+    get_cmd()
+        .arg(COMMAND)
+        .arg(&path)
+        .args(["--amount-col", "amount", "--currency-col", "ccy"])
+        .assert()
+        .failure()
+        .stderr(contains("invalid amount '12,50'"));
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_fail_invalid_args() {
+    get_cmd()
+        .args([
+            COMMAND,
+            "transactions.xlsx",
+            "--amount-col",
+            "a",
+            "--currency-col",
+            "c",
+        ])
+        .assert()
+        .failure()
+        .code(2);
+    get_cmd()
+        .args([COMMAND, "transactions.csv", "--amount-col", "a"])
+        .assert()
+        .failure()
+        .code(2);
+}
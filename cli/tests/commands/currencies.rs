@@ -46,3 +46,25 @@ fn test_raw() {
         .stdout(starts_with("AUD\tAustralian Dollar").and(contains("EUR\tEuro")))
         .success();
 }
+
+#[test]
+fn test_format() {
+    get_cmd()
+        .args([COMMAND, "--format", "csv"])
+        .assert()
+        .stdout(starts_with("code,name\nAUD,Australian Dollar\n").and(contains("\nEUR,Euro\n")))
+        .success();
+    get_cmd()
+        .args([COMMAND, "-f", "TSV"])
+        .assert()
+        .stdout(starts_with("code\tname\nAUD\tAustralian Dollar\n"))
+        .success();
+
+    for args in [
+        &["-f", "xml"][..],
+        &["-f", "csv", "--json"],
+        &["-f", "tsv", "--raw"],
+    ] {
+        get_cmd().arg(COMMAND).args(args).assert().failure().code(2);
+    }
+}
@@ -0,0 +1,76 @@
+use predicates::{prelude::PredicateBooleanExt, str::contains};
+
+use super::INVALID_DATES;
+use crate::shared::get_cmd;
+
+pub(super) const COMMAND: &str = "diff";
+
+// SUCCESS CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_basic() {
+    get_cmd()
+        .args([COMMAND, "2024-01-31", "2024-02-29", "-t", "USD,GBP"])
+        .assert()
+        .success()
+        .stdout(
+            contains("2024-01-31")
+                .and(contains("2024-02-29"))
+                .and(contains("USD"))
+                .and(contains("GBP"))
+                .and(contains("JPY").not())
+                .and(contains("%")),
+        );
+}
+
+#[test]
+fn test_latest() {
+    get_cmd()
+        .args([COMMAND, "2024-01-31", "--base", "usd", "--raw"])
+        .assert()
+        .success()
+        .stdout(
+            contains("EUR\t")
+                .and(contains("\r\n"))
+                .and(contains("USD\t").not()),
+        );
+}
+
+#[test]
+fn test_json() {
+    get_cmd()
+        .args([COMMAND, "2024-01-31", "2024-02-29", "-t", "USD", "--json"])
+        .assert()
+        .success()
+        .stdout(
+            contains("\"changes\"")
+                .and(contains("\"percent\""))
+                .and(contains("\"appeared\""))
+                .and(contains("\"disappeared\"")),
+        );
+}
+
+// FAILURE CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_fail_missing_date() {
+    get_cmd().arg(COMMAND).assert().failure();
+}
+
+#[test]
+fn test_fail_invalid_dates() {
+    for date in INVALID_DATES {
+        get_cmd().args([COMMAND, date]).assert().failure();
+        get_cmd()
+            .args([COMMAND, "2024-01-01", date])
+            .assert()
+            .failure();
+    }
+}
+
+#[test]
+fn test_fail_targets_include_base() {
+    get_cmd()
+        .args([COMMAND, "2024-01-31", "-b", "EUR", "-t", "EUR,USD"])
+        .assert()
+        .failure()
+        .code(2);
+}
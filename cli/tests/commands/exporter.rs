@@ -0,0 +1,55 @@
+use predicates::str::contains;
+
+use crate::shared::{Server, get_cmd};
+
+pub(super) const COMMAND: &str = "exporter";
+
+// SUCCESS CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_metrics() {
+    let server = Server::start(COMMAND, 18093, &["EUR", "USD,GBP"]);
+
+    // The first request for the rates may not have finished yet
+    let mut resp = server.get("/metrics");
+    for _ in 0..50 {
+        if resp.contains("frankfurter_requests_total 1") {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        resp = server.get("/metrics");
+    }
+    assert!(resp.starts_with("http/1.0 200"), "{resp}");
+    assert!(resp.contains("content-type: text/plain; version=0.0.4"));
+    for metric in [
+        "frankfurter_rate{base=\"eur\",target=\"usd\"} ",
+        "frankfurter_rate{base=\"eur\",target=\"gbp\"} ",
+        "frankfurter_rate_age_seconds ",
+        "frankfurter_request_duration_seconds ",
+        "frankfurter_requests_total 1",
+        "frankfurter_request_errors_total 0",
+        "frankfurter_up 1",
+    ] {
+        assert!(resp.contains(metric), "{metric}\n{resp}");
+    }
+    assert!(!resp.contains("target=\"jpy\""));
+}
+
+// FAILURE CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_fail_targets_include_base() {
+    get_cmd()
+        .args([COMMAND, "EUR", "EUR,USD"])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(contains("include the base currency"));
+}
+
+#[test]
+fn test_fail_invalid_interval() {
+    get_cmd()
+        .args([COMMAND, "--interval", "0"])
+        .assert()
+        .failure()
+        .code(2);
+}
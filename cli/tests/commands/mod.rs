@@ -1,6 +1,15 @@
+mod alert;
+mod calc;
 mod convert;
+mod convert_file;
 mod currencies;
+mod diff;
+mod exporter;
 mod period;
+mod plot;
+mod serve;
+mod shorthand;
+mod status;
 
 use crate::shared::get_cmd;
 
@@ -21,7 +30,15 @@ pub(super) const INVALID_DATES: [&str; 11] = [
 // SHARED FAILURE CASES ----------------------------------------------------------------------------
 #[test]
 fn test_fail_raw_and_json() {
-    for cmd in [convert::COMMAND, currencies::COMMAND, period::COMMAND] {
+    for cmd in [
+        alert::COMMAND,
+        calc::COMMAND,
+        convert::COMMAND,
+        currencies::COMMAND,
+        diff::COMMAND,
+        period::COMMAND,
+        status::COMMAND,
+    ] {
         get_cmd().args([cmd, "--raw", "--json"]).assert().failure();
     }
 }
@@ -2,7 +2,11 @@ use std::str::FromStr;
 
 use chrono::Days;
 use lib_frankfurter::ValidDate;
-use predicates::{prelude::PredicateBooleanExt, str::contains};
+use predicates::{
+    Predicate,
+    prelude::PredicateBooleanExt,
+    str::{contains, is_match},
+};
 
 use super::INVALID_DATES;
 use crate::shared::get_cmd;
@@ -33,6 +37,18 @@ fn test_no_base() {
         .success();
 }
 
+#[test]
+fn test_amount_precision() {
+    for (amount, precision) in [("0.005", "3"), ("1234.5678", "4"), ("0.00012345", "8")] {
+        get_cmd()
+            .args([COMMAND, "-s", "2024-01-02", "-e", "2024-01-03"])
+            .args(["-a", amount, "--precision", precision, "--json"])
+            .assert()
+            .stdout(contains(format!("\"amount\": {amount}")))
+            .success();
+    }
+}
+
 #[test]
 fn test_start_date() {
     for date in [*ValidDate::min(), *ValidDate::max()] {
@@ -81,7 +97,323 @@ fn test_end_date() {
     }
 }
 
+#[test]
+fn test_format() {
+    // Long format, with a record per date and currency
+    get_cmd()
+        .args([
+            COMMAND,
+            "EUR",
+            "USD,GBP",
+            "-s",
+            "2024-01-02",
+            "-e",
+            "2024-01-03",
+            "-f",
+            "csv",
+        ])
+        .assert()
+        .stdout(
+            is_match(
+                "^date,base,currency,value\n\
+             2024-01-02,EUR,GBP,[\\d.]+\n\
+             2024-01-02,EUR,USD,[\\d.]+\n\
+             2024-01-03,EUR,GBP,[\\d.]+\n\
+             2024-01-03,EUR,USD,[\\d.]+\n$",
+            )
+            .unwrap(),
+        )
+        .success();
+}
+
+#[test]
+fn test_pivot() {
+    let args = [
+        COMMAND,
+        "EUR",
+        "USD,GBP",
+        "-s",
+        "2024-01-02",
+        "-e",
+        "2024-01-03",
+        "--pivot",
+    ];
+
+    // A row per date, with the date only in the first column
+    get_cmd()
+        .args(args)
+        .assert()
+        .stdout(
+            is_match("Date +┆ +GBP +┆ +USD")
+                .unwrap()
+                .and(is_match("2024-01-02 ┆ [\\d.]+ +┆ [\\d.]+").unwrap())
+                .and(is_match("2024-01-03 ┆ [\\d.]+ +┆ [\\d.]+").unwrap()),
+        )
+        .success();
+    get_cmd()
+        .args(args)
+        .args(["-f", "csv"])
+        .assert()
+        .stdout(
+            is_match("^date,GBP,USD\n2024-01-02,[\\d.]+,[\\d.]+\n2024-01-03,[\\d.]+,[\\d.]+\n$")
+                .unwrap(),
+        )
+        .success();
+    get_cmd()
+        .args(args)
+        .arg("--json")
+        .assert()
+        .stdout(
+            contains("\"currencies\": [\n    \"GBP\",\n    \"USD\"\n  ]").and(contains("\"rows\"")),
+        )
+        .success();
+}
+
+#[test]
+fn test_chart() {
+    get_cmd()
+        .env("COLUMNS", "60")
+        .args([
+            COMMAND,
+            "EUR",
+            "USD,GBP",
+            "-s",
+            "2024-01-02",
+            "-e",
+            "2024-01-31",
+            "--chart",
+        ])
+        .assert()
+        .stdout(
+            is_match("[\\d.]+ ┤[\u{2800}-\u{28FF}]{48}\n")
+                .unwrap()
+                .and(contains(format!("└{}", "─".repeat(48))))
+                .and(contains("2024-01-02"))
+                .and(contains("■ GBP  ■ USD")),
+        )
+        .success();
+}
+
+#[test]
+fn test_sparklines() {
+    get_cmd()
+        .args([
+            COMMAND,
+            "EUR",
+            "USD,GBP",
+            "-s",
+            "2024-01-02",
+            "-e",
+            "2024-01-31",
+            "--sparklines",
+        ])
+        .assert()
+        .stdout(
+            is_match("Date +┆ Currency ┆ Value +┆ Trend +")
+                .unwrap()
+                .and(is_match("2024-01-02 ┆ +GBP +┆ [\\d.]+ +┆ [▁-█] +│").unwrap())
+                .and(is_match("┆ +USD +┆ [\\d.]+ +┆ [▁-█ ]{2,} +│").unwrap()),
+        )
+        .success();
+}
+
+#[test]
+fn test_prices() {
+    get_cmd()
+        .args([
+            COMMAND,
+            "EUR",
+            "USD,GBP",
+            "-s",
+            "2024-01-02",
+            "-e",
+            "2024-01-03",
+        ])
+        .args([
+            "--prices",
+            "hledger",
+            "--commodity",
+            "EUR=€",
+            "--price-decimals",
+            "2",
+        ])
+        .assert()
+        .stdout(
+            is_match(
+                "^P 2024-01-02 GBP [\\d.]+ €\nP 2024-01-02 USD 0\\.\\d{2} €\n\
+                 P 2024-01-03 GBP [\\d.]+ €\nP 2024-01-03 USD 0\\.\\d{2} €\n$",
+            )
+            .unwrap(),
+        )
+        .success();
+}
+
+#[test]
+fn test_prices_append() {
+    let path = std::env::temp_dir().join(format!("frs-prices-{}.beancount", std::process::id()));
+    std::fs::write(&path, "2024-01-02 price USD 0.91 EUR").unwrap();
+
+    get_cmd()
+        .args([
+            COMMAND,
+            "EUR",
+            "USD",
+            "-s",
+            "2024-01-02",
+            "-e",
+            "2024-01-03",
+        ])
+        .args(["--prices", "beancount", "--append"])
+        .arg(&path)
+        .assert()
+        .stdout(contains("Appended 1 prices"))
+        .success();
+
+    // Prices of the same commodity in another base currency are still appended
+    get_cmd()
+        .args([
+            COMMAND,
+            "GBP",
+            "USD",
+            "-s",
+            "2024-01-02",
+            "-e",
+            "2024-01-03",
+        ])
+        .args(["--prices", "beancount", "--append"])
+        .arg(&path)
+        .assert()
+        .stdout(contains("Appended 2 prices"))
+        .success();
+
+    let prices = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(
+        is_match(
+            "^2024-01-02 price USD 0.91 EUR\n2024-01-03 price USD [\\d.]+ EUR\n\
+             2024-01-02 price USD [\\d.]+ GBP\n2024-01-03 price USD [\\d.]+ GBP\n$"
+        )
+        .unwrap()
+        .eval(&prices),
+        "{prices}"
+    );
+}
+
+#[test]
+fn test_gnucash() {
+    let args = [
+        COMMAND,
+        "EUR",
+        "USD,GBP",
+        "-s",
+        "2024-01-02",
+        "-e",
+        "2024-01-02",
+        "--format",
+    ];
+
+    // Prices of one unit of each target currency in the base currency
+    get_cmd()
+        .args(args)
+        .arg("gnucash")
+        .assert()
+        .stdout(
+            is_match(
+                "^date,commodity,currency,price\n\
+                 2024-01-02,GBP,EUR,[\\d.]+\n\
+                 2024-01-02,USD,EUR,0\\.[\\d]+\n$",
+            )
+            .unwrap(),
+        )
+        .success();
+    get_cmd()
+        .args(args)
+        .arg("gnucash-quoted")
+        .assert()
+        .stdout(
+            is_match(
+                "^\"date\",\"commodity\",\"currency\",\"price\"\n\
+                 \"2024-01-02\",\"GBP\",\"EUR\",[\\d.]+\n\
+                 \"2024-01-02\",\"USD\",\"EUR\",0\\.[\\d]+\n$",
+            )
+            .unwrap(),
+        )
+        .success();
+
+    // Also available alongside the other price formats
+    let output = get_cmd().args(args).arg("gnucash").output().unwrap();
+    get_cmd()
+        .args(&args[..args.len() - 1])
+        .args(["--prices", "gnucash"])
+        .assert()
+        .stdout(String::from_utf8(output.stdout).unwrap())
+        .success();
+}
+
 // FAILURE CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_fail_prices_conflicts() {
+    for arg in ["--pivot", "--chart", "--json", "--raw"] {
+        get_cmd()
+            .args([COMMAND, "--prices", "ledger", arg])
+            .assert()
+            .failure()
+            .code(2);
+    }
+    get_cmd()
+        .args([COMMAND, "--commodity", "USD=$"])
+        .assert()
+        .failure()
+        .code(2);
+}
+
+#[test]
+fn test_fail_chart_conflicts() {
+    for arg in ["--pivot", "--sparklines", "--json", "--raw"] {
+        get_cmd()
+            .args([COMMAND, "--chart", arg])
+            .assert()
+            .failure()
+            .code(2);
+    }
+}
+
+#[test]
+fn test_fail_pivot_raw() {
+    get_cmd()
+        .args([COMMAND, "--pivot", "--raw"])
+        .assert()
+        .failure()
+        .code(2);
+}
+
+#[test]
+fn test_fail_gnucash_pivot() {
+    for format in ["gnucash", "gnucash-quoted"] {
+        get_cmd()
+            .args([COMMAND, "--pivot", "-f", format])
+            .assert()
+            .stderr(contains("cannot be used with '--pivot'"))
+            .failure()
+            .code(2);
+    }
+}
+
+#[test]
+fn test_fail_amount_below_precision() {
+    for (amount, precision) in [("0.005", "2"), ("0.5", "0"), ("0.000000001", "8")] {
+        get_cmd()
+            .args([COMMAND, "-a", amount, "--precision", precision])
+            .assert()
+            .failure()
+            .code(2);
+    }
+    get_cmd()
+        .args([COMMAND, "--precision", "9"])
+        .assert()
+        .failure();
+}
+
 #[test]
 fn test_fail_invalid_start_date() {
     for date in INVALID_DATES {
@@ -0,0 +1,103 @@
+use std::{fs, path::PathBuf};
+
+use predicates::str::{contains, ends_with};
+
+use crate::shared::get_cmd;
+
+pub(super) const COMMAND: &str = "plot";
+
+/// Path of a file in the temporary directory, unique to this test run.
+fn output(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("frs-plot-{}-{name}", std::process::id()))
+}
+
+// SUCCESS CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_svg() {
+    let path = output("rates.svg");
+
+    get_cmd()
+        .args([
+            COMMAND,
+            "EUR",
+            "USD,GBP",
+            "-s",
+            "2024-01-02",
+            "-e",
+            "2024-01-31",
+            "-o",
+        ])
+        .arg(&path)
+        .args(["--width", "800", "--height", "400", "--moving-average", "5"])
+        .assert()
+        .stdout(ends_with("rates.svg\n"))
+        .success();
+
+    let svg = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(svg.starts_with(r#"<svg width="800" height="400""#));
+    assert!(svg.contains("EUR exchange rates, 2024-01-02 to 2024-01-31"));
+    assert!(svg.contains("USD 5-rate moving average"));
+    assert!(svg.contains("<polyline"));
+}
+
+#[test]
+fn test_png() {
+    let path = output("rates.png");
+
+    get_cmd()
+        .args([
+            COMMAND,
+            "EUR",
+            "USD",
+            "-s",
+            "2024-01-02",
+            "--log",
+            "-t",
+            "Rates",
+            "-o",
+        ])
+        .arg(&path)
+        .assert()
+        .success();
+
+    let png = fs::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+}
+
+// FAILURE CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_fail_output_format() {
+    for file in ["rates.jpg", "rates"] {
+        get_cmd()
+            .args([COMMAND, "EUR", "-s", "2024-01-02", "-o", file])
+            .assert()
+            .failure()
+            .code(2);
+    }
+}
+
+#[test]
+fn test_fail_missing_args() {
+    get_cmd()
+        .args([COMMAND, "EUR", "-o", "rates.svg"])
+        .assert()
+        .failure()
+        .code(2);
+    get_cmd()
+        .args([COMMAND, "EUR", "-s", "2024-01-02"])
+        .assert()
+        .failure()
+        .code(2);
+}
+
+#[test]
+fn test_fail_invalid_font() {
+    get_cmd()
+        .args([COMMAND, "EUR", "-s", "2024-01-02", "-o", "rates.svg"])
+        .args(["--font", "Cargo.toml"])
+        .assert()
+        .failure()
+        .stderr(contains("Invalid font"));
+}
@@ -0,0 +1,73 @@
+use predicates::{prelude::PredicateBooleanExt, str::contains};
+
+use crate::shared::{BIN, Server, get_cmd};
+
+pub(super) const COMMAND: &str = "serve";
+
+// SUCCESS CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_proxy() {
+    let server = Server::start(COMMAND, 18091, &[]);
+
+    for cmd in [
+        vec!["convert", "EUR", "USD"],
+        vec!["convert", "-d", "2024-01-31"],
+        vec![
+            "period",
+            "-s",
+            "2024-01-01",
+            "-e",
+            "2024-01-10",
+            "GBP",
+            "USD",
+        ],
+        vec!["currencies"],
+        vec!["status"],
+    ] {
+        assert_cmd::Command::cargo_bin(BIN)
+            .unwrap()
+            .arg(server.url())
+            .args(cmd)
+            .assert()
+            .success();
+    }
+}
+
+#[test]
+fn test_cache() {
+    let server = Server::start(COMMAND, 18092, &[]);
+
+    // MISS THEN HIT
+    let resp = server.get("/v1/2024-01-31?base=USD&symbols=EUR");
+    assert!(resp.starts_with("http/1.0 200"), "{resp}");
+    assert!(resp.contains("x-cache: miss"));
+    assert!(resp.contains("\"date\":\"2024-01-31\""));
+
+    // Aliases of the query parameters share the same cache entry
+    let resp = server.get("/v1/2024-01-31?from=USD&to=EUR");
+    assert!(resp.contains("x-cache: hit"), "{resp}");
+
+    let resp = server.get("/v1/2024-01-01..2024-01-10?symbols=USD");
+    assert!(resp.contains("x-cache: miss"));
+    let resp = server.get("/v1/2024-01-01..2024-01-10?symbols=USD");
+    assert!(resp.contains("x-cache: hit"));
+
+    // ERRORS ARE NOT CACHED
+    for _ in 0..2 {
+        let resp = server.get("/v1/latest?base=XYZ");
+        assert!(resp.starts_with("http/1.0 404"), "{resp}");
+        assert!(!resp.contains("x-cache"));
+    }
+    assert!(server.get("/v1/2024-13-01").starts_with("http/1.0 422"));
+}
+
+// FAILURE CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_fail_invalid_address() {
+    get_cmd()
+        .args([COMMAND, "--address", "localhost"])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(contains("invalid value").and(contains("--address")));
+}
@@ -0,0 +1,104 @@
+use assert_cmd::Command;
+use predicates::{
+    prelude::PredicateBooleanExt,
+    str::{contains, is_match},
+};
+
+use crate::shared::{BIN, get_cmd};
+
+// SUCCESS CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_basic() {
+    let convert = get_cmd()
+        .args([
+            "convert",
+            "USD",
+            "EUR",
+            "-a",
+            "100",
+            "-d",
+            "2024-01-09",
+            "--raw",
+        ])
+        .output()
+        .unwrap()
+        .stdout;
+
+    get_cmd()
+        .args(["100", "usd", "to", "eur", "on", "2024-01-09", "--raw"])
+        .assert()
+        .success()
+        .stdout(String::from_utf8(convert).unwrap());
+}
+
+#[test]
+fn test_suffix_and_targets() {
+    get_cmd()
+        .args(["2.5k", "gbp", "in", "jpy,usd", "--json"])
+        .assert()
+        .success()
+        .stdout(
+            contains("\"base\": \"GBP\"")
+                .and(contains("\"amount\": 2500.0"))
+                .and(contains("\"JPY\""))
+                .and(contains("\"USD\""))
+                .and(contains("\"EUR\"").not()),
+        );
+}
+
+#[test]
+fn test_without_amount() {
+    get_cmd()
+        .args(["usd", "eur"])
+        .assert()
+        .success()
+        .stdout(is_match("EUR ┆ [\\d.]+").unwrap());
+}
+
+#[test]
+fn test_global_options_after_query() {
+    Command::cargo_bin(BIN)
+        .unwrap()
+        .args([
+            "100",
+            "usd",
+            "to",
+            "eur",
+            "--raw",
+            "--url",
+            "http://localhost:8080",
+        ])
+        .args(["-d", "2024-01-09", "--color=never"])
+        .assert()
+        .success()
+        .stdout(is_match("^EUR\t[\\d.]+\r?\n$").unwrap());
+
+    // The URL after the query is used
+    Command::cargo_bin(BIN)
+        .unwrap()
+        .args(["100", "usd", "to", "eur", "--url=http://localhost:1"])
+        .assert()
+        .failure()
+        .code(3);
+}
+
+// FAILURE CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_fail_invalid_query() {
+    for args in [
+        &["100", "dollars", "to", "eur"][..],
+        &["usd", "to"],
+        &["usd", "eur", "on"],
+        &["usd", "eur", "on", "2024-13-01"],
+        &["usd", "eur", "--bogus"],
+    ] {
+        get_cmd().args(args).assert().failure().code(2);
+    }
+
+    get_cmd()
+        .arg("conver")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(contains("unrecognized subcommand or currency 'conver'"));
+}
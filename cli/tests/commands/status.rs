@@ -0,0 +1,40 @@
+use predicates::{prelude::PredicateBooleanExt, str::contains};
+
+use assert_cmd::Command;
+
+use crate::shared::{BIN, get_cmd};
+
+pub(super) const COMMAND: &str = "status";
+
+// SUCCESS CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_basic() {
+    get_cmd().arg(COMMAND).assert().stdout(
+        contains("Reachable")
+            .and(contains("yes"))
+            .and(contains("v1"))
+            .and(contains("Latest date"))
+            .and(contains("Expected date")),
+    );
+}
+
+#[test]
+fn test_json() {
+    get_cmd().args([COMMAND, "--json"]).assert().stdout(
+        contains("\"reachable\": true")
+            .and(contains("\"api_version\": \"v1\""))
+            .and(contains("\"stale\"")),
+    );
+}
+
+// FAILURE CASES -----------------------------------------------------------------------------------
+#[test]
+fn test_fail_unreachable() {
+    Command::cargo_bin(BIN)
+        .unwrap()
+        .args(["--url=http://localhost:1", COMMAND])
+        .assert()
+        .stdout(contains("Reachable").and(contains("no")))
+        .failure()
+        .code(3);
+}
@@ -13,7 +13,22 @@ fn test_invalid_api() {
         .arg("currencies")
         .assert()
         .stderr(contains("error sending request"))
-        .failure();
+        .failure()
+        .code(3);
+}
+
+#[test]
+fn test_invalid_api_with_fallback() {
+    Command::cargo_bin(BIN)
+        .unwrap()
+        .args([
+            "--url=http://localhost",
+            "--fallback-url=http://localhost:8080",
+            "currencies",
+        ])
+        .assert()
+        .stdout(contains("EUR"))
+        .success();
 }
 
 #[test]
@@ -30,5 +45,17 @@ fn test_invalid_api_endpoint() {
                 .and(contains("Status"))
                 .and(contains("404")),
         )
-        .failure();
+        .failure()
+        .code(4);
+}
+
+#[test]
+fn test_invalid_request() {
+    Command::cargo_bin(BIN)
+        .unwrap()
+        .args(["convert", "USD", "EUR,USD"])
+        .assert()
+        .stderr(contains("include the base currency"))
+        .failure()
+        .code(2);
 }
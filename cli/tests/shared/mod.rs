@@ -1,6 +1,12 @@
 #![allow(dead_code)]
 
-use assert_cmd::Command;
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpStream,
+    process::{Child, Stdio},
+};
+
+use assert_cmd::{Command, cargo::cargo_bin};
 
 pub const BIN: &str = "frs";
 
@@ -9,3 +15,56 @@ pub fn get_cmd() -> Command {
     cmd.arg("--url=http://localhost:8080");
     cmd
 }
+
+/// Running `frs` subcommand which serves HTTP, e.g. `serve`, killed when dropped.
+pub struct Server {
+    child: Child,
+    address: String,
+}
+
+impl Server {
+    /// Start the subcommand listening on the given port, waiting until it is listening.
+    pub fn start(command: &str, port: u16, args: &[&str]) -> Self {
+        let address = format!("127.0.0.1:{port}");
+        let mut child = std::process::Command::new(cargo_bin(BIN))
+            .args([
+                "--url=http://localhost:8080",
+                command,
+                "--address",
+                &address,
+            ])
+            .args(args)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let mut line = String::new();
+        BufReader::new(child.stdout.as_mut().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        assert!(line.contains(&address), "{line}");
+
+        Self { child, address }
+    }
+
+    pub fn url(&self) -> String {
+        format!("--url=http://{}", self.address)
+    }
+
+    /// Make a raw HTTP request, returning the full response including headers.
+    pub fn get(&self, path: &str) -> String {
+        let mut stream = TcpStream::connect(&self.address).unwrap();
+        write!(stream, "GET {path} HTTP/1.0\r\nHost: localhost\r\n\r\n").unwrap();
+
+        let mut resp = String::new();
+        stream.read_to_string(&mut resp).unwrap();
+        resp.to_lowercase()
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
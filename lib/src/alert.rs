@@ -0,0 +1,425 @@
+//! [`Rule`]s for alerting on changes in exchange rates, evaluated against the responses of the
+//! [`convert`] and [`period`] endpoints.
+//!
+//! # Example
+//! ```no_run
+//! # use lib_frankfurter::{alert::{Condition, Rule}, api::ServerClient};
+//! # async fn run() -> Result<(), lib_frankfurter::Error> {
+//! let server_client = ServerClient::default();
+//!
+//! // EUR/USD moving by more than 1% in a day
+//! let rule = Rule::new("EUR/USD".parse()?, Condition::DailyMove(1.0))?;
+//! let response = server_client.period(rule.period_request()).await?;
+//!
+//! if let Some(evaluation) = rule.check_period(&response).filter(|e| e.fired) {
+//!     println!("{evaluation}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{convert, period},
+    data::{Currency, CurrencyValue, CurrencyValueMap, ValidDate},
+    error::{Error, Result},
+};
+
+/// Number of calendar days of rates to request for rules which only compare the latest rate to the
+/// previous one, enough to cover the longest gap between two days with rates (e.g. over Easter).
+const MIN_LOOKBACK_DAYS: u64 = 10;
+
+// PAIR --------------------------------------------------------------------------------------------
+/// A currency pair, where the rate is the value of one unit of the `base` in the `target`.
+///
+/// # Example
+/// ```
+/// # use lib_frankfurter::{Currency, alert::Pair};
+/// let pair: Pair = "eur/usd".parse().unwrap();
+/// assert_eq!(pair.base, Currency::EUR);
+/// assert_eq!(pair.target, Currency::USD);
+/// assert_eq!(pair.to_string(), "EUR/USD");
+///
+/// assert!("EUR".parse::<Pair>().is_err());
+/// assert!("EUR/EUR".parse::<Pair>().is_err());
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Deserialize, Serialize)]
+pub struct Pair {
+    pub base: Currency,
+    pub target: Currency,
+}
+
+impl Pair {
+    /// Get the rate of the pair from a map of rates, which were exchanged from `amount` of `base`.
+    ///
+    /// The base of the rates doesn't need to match the base of the pair, so long as both
+    /// currencies of the pair are present.
+    fn rate(
+        &self,
+        base: &Currency,
+        amount: CurrencyValue,
+        rates: &CurrencyValueMap,
+    ) -> Option<f64> {
+        let value = |currency: &Currency| {
+            if currency == base {
+                Some(*amount)
+            } else {
+                rates.get(currency).map(|v| **v)
+            }
+        };
+
+        Some(value(&self.target)? / value(&self.base)?)
+    }
+}
+
+impl FromStr for Pair {
+    type Err = Error;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || Error::InvalidAlertRule(s.to_owned());
+
+        let (base, target) = s.split_once('/').ok_or_else(invalid)?;
+        let base = Currency::from_str(base.trim()).map_err(|_| invalid())?;
+        let target = Currency::from_str(target.trim()).map_err(|_| invalid())?;
+
+        if base == target {
+            return Err(invalid());
+        }
+
+        Ok(Self { base, target })
+    }
+}
+
+impl Display for Pair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.base, self.target)
+    }
+}
+
+// CONDITION ---------------------------------------------------------------------------------------
+/// Condition under which a [`Rule`] fires.
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    /// The rate crosses above the given level.
+    ///
+    /// Without a previous rate to compare to, fires whenever the rate is above the level.
+    Above(f64),
+    /// The rate crosses below the given level.
+    ///
+    /// Without a previous rate to compare to, fires whenever the rate is below the level.
+    Below(f64),
+    /// The rate moves up or down by more than the given percentage since the previous day with
+    /// rates.
+    DailyMove(f64),
+    /// The latest rate is higher than the rates for the given number of previous days with rates.
+    High(usize),
+    /// The latest rate is lower than the rates for the given number of previous days with rates.
+    Low(usize),
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Above(level) => write!(f, "above {level}"),
+            Self::Below(level) => write!(f, "below {level}"),
+            Self::DailyMove(percent) => write!(f, "daily move over {percent}%"),
+            Self::High(days) => write!(f, "{days}-day high"),
+            Self::Low(days) => write!(f, "{days}-day low"),
+        }
+    }
+}
+
+// RULE --------------------------------------------------------------------------------------------
+/// A [`Condition`] on the rate of a currency [`Pair`].
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct Rule {
+    pub pair: Pair,
+    pub condition: Condition,
+}
+
+impl Rule {
+    /// Create a new [`Rule`], ensuring that the levels and percentages of the condition are
+    /// positive numbers, and the number of days is at least 1.
+    pub fn new(pair: Pair, condition: Condition) -> Result<Self> {
+        let is_valid = match condition {
+            Condition::Above(n) | Condition::Below(n) | Condition::DailyMove(n) => {
+                n.is_finite() && n > 0.0
+            }
+            Condition::High(days) | Condition::Low(days) => days > 0,
+        };
+
+        if !is_valid {
+            return Err(Error::InvalidAlertRule(format!("{pair} {condition}")));
+        }
+
+        Ok(Self { pair, condition })
+    }
+
+    /// Get a [`period::Request`] for enough of the most recent rates to evaluate the rule, see
+    /// [`Rule::check_period`].
+    pub fn period_request(&self) -> period::Request {
+        let days = match self.condition {
+            Condition::High(days) | Condition::Low(days) => {
+                // Roughly 5 days with rates per week
+                (days as u64 * 7).div_ceil(5) + MIN_LOOKBACK_DAYS
+            }
+            _ => MIN_LOOKBACK_DAYS,
+        };
+
+        let start = ValidDate::max()
+            .checked_sub_days(Days::new(days))
+            .and_then(|d| ValidDate::try_from(d).ok())
+            .unwrap_or_else(ValidDate::min);
+
+        period::Request::default()
+            .with_base(self.pair.base.clone())
+            .with_targets(vec![self.pair.target.clone()])
+            .with_start_date(start)
+    }
+
+    /// Evaluate the rule against the rates of a single day.
+    ///
+    /// Returns [`None`] if the response doesn't contain the rate for the pair, or if the condition
+    /// requires previous rates, i.e. [`Condition::DailyMove`], [`Condition::High`] and
+    /// [`Condition::Low`].
+    pub fn check_convert(&self, response: &convert::Response) -> Option<Evaluation> {
+        let rate = self
+            .pair
+            .rate(&response.base, response.amount, &response.rates)?;
+        self.evaluate(&[(*response.date, rate)])
+    }
+
+    /// Evaluate the rule against the latest rates of a time period, comparing them to the
+    /// preceding rates where necessary.
+    ///
+    /// Returns [`None`] if the response doesn't contain enough rates for the pair.
+    pub fn check_period(&self, response: &period::Response) -> Option<Evaluation> {
+        let series = response
+            .rates
+            .iter()
+            .filter_map(|(date, rates)| {
+                self.pair
+                    .rate(&response.base, response.amount, rates)
+                    .map(|rate| (**date, rate))
+            })
+            .collect::<Vec<_>>();
+        self.evaluate(&series)
+    }
+
+    /// Evaluate the rule against a series of rates, sorted by date.
+    fn evaluate(&self, series: &[(NaiveDate, f64)]) -> Option<Evaluation> {
+        let (&(date, rate), rest) = series.split_last()?;
+        let previous = rest.last().map(|(_, rate)| *rate);
+
+        let (reference, fired) = match self.condition {
+            Condition::Above(level) => (
+                previous,
+                rate > level && previous.is_none_or(|p| p <= level),
+            ),
+            Condition::Below(level) => (
+                previous,
+                rate < level && previous.is_none_or(|p| p >= level),
+            ),
+            Condition::DailyMove(percent) => {
+                let previous = previous?;
+                (
+                    Some(previous),
+                    ((rate - previous) / previous * 100.0).abs() > percent,
+                )
+            }
+            Condition::High(days) | Condition::Low(days) => {
+                let window = rest
+                    .get(rest.len().checked_sub(days)?..)?
+                    .iter()
+                    .map(|(_, r)| *r);
+                if matches!(self.condition, Condition::High(_)) {
+                    let high = window.fold(f64::MIN, f64::max);
+                    (Some(high), rate > high)
+                } else {
+                    let low = window.fold(f64::MAX, f64::min);
+                    (Some(low), rate < low)
+                }
+            }
+        };
+
+        Some(Evaluation {
+            rule: self.clone(),
+            date,
+            rate,
+            reference,
+            fired,
+        })
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.pair, self.condition)
+    }
+}
+
+// EVALUATION --------------------------------------------------------------------------------------
+/// Result of evaluating a [`Rule`], see [`Rule::check_period`] and [`Rule::check_convert`].
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct Evaluation {
+    /// Rule which was evaluated.
+    pub rule: Rule,
+    /// Date of the latest rate.
+    pub date: NaiveDate,
+    /// Latest rate of the pair.
+    pub rate: f64,
+    /// Rate the latest rate was compared to, i.e. the previous rate for level crossings and daily
+    /// moves, or the highest or lowest of the previous rates for N-day highs and lows.
+    pub reference: Option<f64>,
+    /// Whether the condition of the rule was met.
+    pub fired: bool,
+}
+
+impl Evaluation {
+    /// Percentage change from the reference rate to the latest rate.
+    pub fn change_percent(&self) -> Option<f64> {
+        self.reference.map(|r| (self.rate - r) / r * 100.0)
+    }
+}
+
+impl Display for Evaluation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {:.4} on {}", self.rule, self.rate, self.date)?;
+
+        if let (Some(reference), Some(change)) = (self.reference, self.change_percent()) {
+            write!(f, " ({change:+.2}% from {reference:.4})")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests_alert {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn rule(condition: Condition) -> Rule {
+        Rule::new(Pair::from_str("EUR/USD").unwrap(), condition).unwrap()
+    }
+
+    fn series(rates: &[f64]) -> Vec<(NaiveDate, f64)> {
+        rates
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (NaiveDate::from_ymd_opt(2024, 1, i as u32 + 1).unwrap(), *r))
+            .collect()
+    }
+
+    fn fired(condition: Condition, rates: &[f64]) -> Option<bool> {
+        rule(condition).evaluate(&series(rates)).map(|e| e.fired)
+    }
+
+    #[test]
+    fn test_pair() {
+        let pair = Pair::from_str(" gbp / jpy ").unwrap();
+        assert_eq!(pair.base, Currency::GBP);
+        assert_eq!(pair.target, Currency::JPY);
+
+        for s in ["", "EUR", "EUR/", "EUR-USD", "USD/USD"] {
+            assert!(Pair::from_str(s).is_err(), "{s}");
+        }
+
+        // Cross rates
+        let rates = CurrencyValueMap::from([
+            (Currency::USD, CurrencyValue::try_from(2.2).unwrap()),
+            (Currency::GBP, CurrencyValue::try_from(1.6).unwrap()),
+        ]);
+        let amount = CurrencyValue::try_from(2.0).unwrap();
+        let rate = |s| {
+            Pair::from_str(s)
+                .unwrap()
+                .rate(&Currency::EUR, amount, &rates)
+        };
+        assert_eq!(rate("EUR/USD"), Some(1.1));
+        assert_eq!(rate("USD/EUR"), Some(2.0 / 2.2));
+        assert_eq!(rate("GBP/USD"), Some(2.2 / 1.6));
+        assert_eq!(rate("EUR/JPY"), None);
+    }
+
+    #[test]
+    fn test_rule_validation() {
+        let pair = Pair::from_str("EUR/USD").unwrap();
+        for condition in [
+            Condition::Above(0.0),
+            Condition::Below(-1.0),
+            Condition::DailyMove(f64::NAN),
+            Condition::High(0),
+        ] {
+            assert!(Rule::new(pair.clone(), condition).is_err());
+        }
+    }
+
+    #[test]
+    fn test_level_crossing() {
+        // ABOVE
+        assert_eq!(fired(Condition::Above(1.1), &[1.09, 1.11]), Some(true));
+        assert_eq!(fired(Condition::Above(1.1), &[1.1, 1.11]), Some(true));
+        // Already above
+        assert_eq!(fired(Condition::Above(1.1), &[1.12, 1.11]), Some(false));
+        assert_eq!(fired(Condition::Above(1.1), &[1.11, 1.09]), Some(false));
+        // No previous rate
+        assert_eq!(fired(Condition::Above(1.1), &[1.11]), Some(true));
+
+        // BELOW
+        assert_eq!(fired(Condition::Below(1.1), &[1.11, 1.09]), Some(true));
+        assert_eq!(fired(Condition::Below(1.1), &[1.09, 1.08]), Some(false));
+        assert_eq!(fired(Condition::Below(1.1), &[1.09]), Some(true));
+
+        assert_eq!(fired(Condition::Above(1.1), &[]), None);
+    }
+
+    #[test]
+    fn test_daily_move() {
+        assert_eq!(fired(Condition::DailyMove(1.0), &[1.0, 1.02]), Some(true));
+        assert_eq!(fired(Condition::DailyMove(1.0), &[1.0, 0.98]), Some(true));
+        assert_eq!(fired(Condition::DailyMove(1.0), &[1.0, 1.005]), Some(false));
+        // Only the latest move counts
+        assert_eq!(
+            fired(Condition::DailyMove(1.0), &[1.0, 1.05, 1.055]),
+            Some(false)
+        );
+
+        assert_eq!(fired(Condition::DailyMove(1.0), &[1.0]), None);
+    }
+
+    #[test]
+    fn test_high_low() {
+        let rates = [1.0, 1.3, 1.1, 1.2, 1.25];
+
+        // HIGH
+        assert_eq!(fired(Condition::High(2), &rates), Some(true));
+        assert_eq!(fired(Condition::High(3), &rates), Some(false));
+        assert_eq!(fired(Condition::High(4), &rates), Some(false));
+        assert_eq!(fired(Condition::High(5), &rates), None);
+
+        // LOW
+        assert_eq!(fired(Condition::Low(1), &[1.0, 0.9]), Some(true));
+        assert_eq!(fired(Condition::Low(2), &[0.8, 1.0, 0.9]), Some(false));
+
+        let evaluation = rule(Condition::High(2)).evaluate(&series(&rates)).unwrap();
+        assert_eq!(evaluation.reference, Some(1.2));
+        assert_eq!(evaluation.rate, 1.25);
+    }
+
+    #[test]
+    fn test_evaluation_display() {
+        let evaluation = rule(Condition::DailyMove(1.0))
+            .evaluate(&series(&[1.0, 1.02]))
+            .unwrap();
+        assert_eq!(
+            evaluation.to_string(),
+            "EUR/USD daily move over 1%: 1.0200 on 2024-01-02 (+2.00% from 1.0000)"
+        );
+    }
+}
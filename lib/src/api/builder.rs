@@ -0,0 +1,298 @@
+//! [`ServerClientBuilder`] for configuring a [`ServerClient`] beyond the defaults.
+
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use reqwest::header::HeaderMap;
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::{Certificate, Proxy};
+use url::Url;
+
+use super::{
+    ServerClient,
+    failover::{DEFAULT_COOLDOWN, Endpoint},
+    fixtures::{FixtureMode, Fixtures},
+    middleware::Middleware,
+};
+use crate::error::Result;
+
+/// Path appended to the base URL to use the correct version of the API.
+pub(crate) const DEFAULT_API_VERSION_PATH: &str = "v1";
+
+/// Builder for a [`ServerClient`], see [`ServerClient::builder`].
+///
+/// # Example
+/// ```
+/// # use std::time::Duration;
+/// # use lib_frankfurter::{api::ServerClient, url::Url};
+/// let server_client = ServerClient::builder(Url::parse("http://localhost:8080/frankfurter").unwrap())
+///     .connect_timeout(Duration::from_secs(2))
+///     .timeout(Duration::from_secs(10))
+///     .user_agent("my-app/1.0")
+///     .build()
+///     .unwrap();
+/// ```
+#[must_use]
+pub struct ServerClientBuilder {
+    url: Url,
+    fallback_urls: Vec<Url>,
+    failover_cooldown: Duration,
+    api_version_path: Option<String>,
+    strip_trailing_slashes: bool,
+    client: reqwest::ClientBuilder,
+    middleware: Vec<Arc<dyn Middleware>>,
+    fixtures: Option<Fixtures>,
+}
+
+impl ServerClientBuilder {
+    pub(super) fn new(url: Url) -> Self {
+        Self {
+            url,
+            fallback_urls: Vec::new(),
+            failover_cooldown: DEFAULT_COOLDOWN,
+            api_version_path: Some(DEFAULT_API_VERSION_PATH.to_owned()),
+            strip_trailing_slashes: true,
+            client: reqwest::Client::builder(),
+            middleware: Vec::new(),
+            fixtures: None,
+        }
+    }
+
+    /// Add a fallback URL for the API, to be used if the URLs added before it are unreachable or
+    /// respond with a server error.
+    ///
+    /// The same options, e.g. [`Self::api_version_path`], are applied to all URLs.
+    pub fn fallback_url(mut self, url: Url) -> Self {
+        self.fallback_urls.push(url);
+        self
+    }
+
+    /// Set how long a URL is skipped for after a failed request, while there are other URLs to
+    /// fall back on [default: 60 seconds].
+    pub fn failover_cooldown(mut self, cooldown: Duration) -> Self {
+        self.failover_cooldown = cooldown;
+        self
+    }
+
+    /// Set a timeout for only the connect phase of requests.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.client = self.client.connect_timeout(timeout);
+        self
+    }
+
+    /// Set a timeout for each read of the response body, which is reset after each successful read.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.client = self.client.read_timeout(timeout);
+        self
+    }
+
+    /// Set a total timeout for requests, from when they start connecting until the response body
+    /// has been fully read.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.client = self.client.timeout(timeout);
+        self
+    }
+
+    /// Set the `User-Agent` header to be used for every request.
+    ///
+    /// An invalid header value will cause [`Self::build`] to fail.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.client = self.client.user_agent(user_agent.into());
+        self
+    }
+
+    /// Set the default headers to be used for every request.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.client = self.client.default_headers(headers);
+        self
+    }
+
+    /// Add a proxy to use for requests.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.client = self.client.proxy(proxy);
+        self
+    }
+
+    /// Add custom TLS root certificates to trust, in addition to the built-in ones, e.g. for a
+    /// self-hosted API using a private certificate authority.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn root_certificates(
+        mut self,
+        certificates: impl IntoIterator<Item = Certificate>,
+    ) -> Self {
+        self.client = self.client.tls_certs_merge(certificates);
+        self
+    }
+
+    /// Set the path which is appended to the base URL to select the version of the API [default: `v1`].
+    pub fn api_version_path(mut self, path: impl Into<String>) -> Self {
+        self.api_version_path = Some(path.into());
+        self
+    }
+
+    /// Use the base URL as is, without appending a path for the version of the API, e.g. for a
+    /// mirror which is mounted at a different path.
+    pub fn without_api_version_path(mut self) -> Self {
+        self.api_version_path = None;
+        self
+    }
+
+    /// Set whether any trailing `/` should be removed from the base URL [default: `true`].
+    ///
+    /// They are always removed before appending the [`Self::api_version_path`], so this only
+    /// affects URLs used [`Self::without_api_version_path`].
+    pub fn strip_trailing_slashes(mut self, strip: bool) -> Self {
+        self.strip_trailing_slashes = strip;
+        self
+    }
+
+    /// Register a [`Middleware`], see [`ServerClient::with_middleware`].
+    pub fn middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Record responses to, or replay them from, fixtures in the given directory, see
+    /// [`ServerClient::with_fixtures`].
+    pub fn fixtures(mut self, dir: impl Into<PathBuf>, mode: FixtureMode) -> Self {
+        self.fixtures = Some(Fixtures {
+            dir: dir.into(),
+            mode,
+        });
+        self
+    }
+
+    /// Build the [`ServerClient`].
+    ///
+    /// Fails if the inner [`reqwest::Client`] can't be built, e.g. due to an invalid user agent.
+    pub fn build(self) -> Result<ServerClient> {
+        let api_version_path = self.api_version_path.as_deref();
+
+        Ok(ServerClient {
+            endpoints: std::iter::once(self.url)
+                .chain(self.fallback_urls)
+                .map(|url| {
+                    Endpoint::new(build_base_url(
+                        url,
+                        api_version_path,
+                        self.strip_trailing_slashes,
+                    ))
+                })
+                .collect(),
+            client: self.client.build()?,
+            middleware: self.middleware,
+            failover_cooldown: self.failover_cooldown,
+            fixtures: self.fixtures,
+        })
+    }
+}
+
+/// Build the base URL for all requests to the API.
+pub(super) fn build_base_url(
+    mut url: Url,
+    api_version_path: Option<&str>,
+    strip_trailing_slashes: bool,
+) -> Url {
+    // Segments are joined without empty segments between them, e.g. `mirror//v1`
+    if strip_trailing_slashes || api_version_path.is_some() {
+        // Remove any number of trailing `/`
+        while url.path().ends_with('/') && url.path_segments().unwrap().count() != 1 {
+            url.path_segments_mut().unwrap().pop();
+        }
+    }
+
+    if let Some(path) = api_version_path {
+        url.path_segments_mut()
+            .unwrap()
+            .extend(path.split('/').filter(|s| !s.is_empty()));
+    }
+
+    url
+}
+
+#[cfg(test)]
+mod tests_builder {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_build_base_url() {
+        let url = |s: &str| Url::parse(s).unwrap();
+
+        // DEFAULT
+        for base in [
+            "http://localhost:8080",
+            "http://localhost:8080/",
+            "http://localhost:8080///",
+        ] {
+            assert_eq!(
+                build_base_url(url(base), Some(DEFAULT_API_VERSION_PATH), true),
+                url("http://localhost:8080/v1")
+            );
+        }
+        assert_eq!(
+            build_base_url(url("http://localhost/mirror//"), Some("v1"), true),
+            url("http://localhost/mirror/v1")
+        );
+
+        // API VERSION PATH
+        assert_eq!(
+            build_base_url(url("http://localhost/mirror"), None, true),
+            url("http://localhost/mirror")
+        );
+        assert_eq!(
+            build_base_url(url("http://localhost/mirror"), Some("/api/v2/"), true),
+            url("http://localhost/mirror/api/v2")
+        );
+
+        // KEEP TRAILING SLASHES
+        assert_eq!(
+            build_base_url(url("http://localhost/mirror/"), None, false),
+            url("http://localhost/mirror/")
+        );
+        for base in ["http://localhost/mirror/", "http://localhost/mirror//"] {
+            assert_eq!(
+                build_base_url(url(base), Some(DEFAULT_API_VERSION_PATH), false),
+                url("http://localhost/mirror/v1")
+            );
+        }
+        assert_eq!(
+            build_base_url(url("http://localhost/"), Some("/api/v2/"), false),
+            url("http://localhost/api/v2")
+        );
+    }
+
+    #[test]
+    fn test_build() {
+        let client = ServerClient::builder(Url::parse("http://localhost:8080/mirror/").unwrap())
+            .fallback_url(Url::parse("https://api.frankfurter.dev").unwrap())
+            .without_api_version_path()
+            .timeout(Duration::from_secs(1))
+            .user_agent("frankfurters-test")
+            .build()
+            .unwrap();
+        assert_eq!(
+            client
+                .endpoints
+                .iter()
+                .map(|e| e.url.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "http://localhost:8080/mirror",
+                "https://api.frankfurter.dev/"
+            ]
+        );
+
+        // Invalid header value
+        assert!(
+            ServerClient::builder(Url::parse("http://localhost:8080").unwrap())
+                .user_agent("invalid\n")
+                .build()
+                .is_err()
+        );
+    }
+}
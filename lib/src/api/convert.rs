@@ -1,11 +1,11 @@
 //! [`Request`] and [`Response`] types for requesting exchange rates for a specific date (latest by default).
 
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::BTreeMap};
 
 use serde::{Deserialize, Serialize};
 
 use super::{ServerClientRequest, base_build_query_params, base_ensure_valid};
-use crate::data::{Currency, CurrencyValue, CurrencyValueMap, ValidDate};
+use crate::data::{Currency, CurrencyValue, CurrencyValueMap, Quotation, ValidDate};
 
 /// Response for fetching the latest exchange rates.
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
@@ -21,6 +21,17 @@ pub struct Response {
     pub rates: CurrencyValueMap,
 }
 
+impl Response {
+    /// Get the rates for each target currency using the given [`Quotation`], e.g. the amount of
+    /// the base currency for one unit of each target currency.
+    pub fn quote(&self, quotation: Quotation) -> BTreeMap<Currency, f64> {
+        self.rates
+            .iter()
+            .map(|(currency, value)| (currency.clone(), quotation.rate(*self.amount, **value)))
+            .collect()
+    }
+}
+
 /// Request query parameters for fetching the latest exchange rates.
 #[derive(Clone, PartialEq, Debug, Serialize, Default)]
 pub struct Request {
@@ -86,6 +97,23 @@ mod tests_convert {
     use super::*;
     use crate::api::test_utils::dbg_err;
 
+    #[test]
+    fn test_quote() {
+        let resp: Response = serde_json::from_str(
+            r#"{"base": "EUR", "amount": 10.0, "date": "2024-01-09", "rates": {"JPY": 1600.0, "USD": 11.0}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            resp.quote(Quotation::default()),
+            BTreeMap::from([(Currency::JPY, 160.0), (Currency::USD, 1.1)])
+        );
+        assert_eq!(
+            resp.quote(Quotation::Indirect { per: 100 }),
+            BTreeMap::from([(Currency::JPY, 0.625), (Currency::USD, 100.0 / 1.1)])
+        );
+    }
+
     #[test]
     fn test_get_url() {
         assert_eq!(Request::default().get_url(), "latest");
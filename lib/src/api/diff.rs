@@ -0,0 +1,197 @@
+//! [`Diff`] between the exchange rates of two dates, e.g. to see how much each currency moved
+//! since the end of the last month.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::{ServerClient, convert};
+use crate::{
+    data::{Currency, ValidDate},
+    error::{Error, Result},
+};
+
+/// Change in the rate of a single currency between two dates, see [`Diff`].
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct Change {
+    pub currency: Currency,
+    /// Rate for one unit of the base currency on the earlier date.
+    pub from: f64,
+    /// Rate for one unit of the base currency on the later date.
+    pub to: f64,
+    /// Absolute change in the rate, i.e. `to - from`.
+    pub absolute: f64,
+    /// Change in the rate as a percentage of the earlier rate.
+    pub percent: f64,
+}
+
+/// Differences between two [`convert::Response`]s with the same base currency.
+///
+/// Rates are normalised to one unit of the base currency, so responses for different amounts can
+/// be compared.
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct Diff {
+    /// Base currency of both responses.
+    pub base: Currency,
+    /// Date of the rates of the earlier response.
+    pub from_date: ValidDate,
+    /// Date of the rates of the later response.
+    pub to_date: ValidDate,
+    /// Changes for the currencies present in both responses, sorted by the magnitude of the
+    /// percentage change, largest first.
+    pub changes: Vec<Change>,
+    /// Currencies only present in the later response, with their rates.
+    pub appeared: BTreeMap<Currency, f64>,
+    /// Currencies only present in the earlier response, with their rates.
+    pub disappeared: BTreeMap<Currency, f64>,
+}
+
+impl Diff {
+    /// Compare the rates of two responses, which must have the same base currency.
+    ///
+    /// # Example
+    /// ```
+    /// # use lib_frankfurter::{Currency, api::{convert, diff::Diff}};
+    /// let from: convert::Response = serde_json::from_str(
+    ///     r#"{"base": "EUR", "amount": 1.0, "date": "2024-01-31", "rates": {"GBP": 0.85, "USD": 1.1}}"#,
+    /// ).unwrap();
+    /// let to: convert::Response = serde_json::from_str(
+    ///     r#"{"base": "EUR", "amount": 1.0, "date": "2024-02-29", "rates": {"GBP": 0.86, "USD": 1.0}}"#,
+    /// ).unwrap();
+    ///
+    /// let diff = Diff::new(&from, &to).unwrap();
+    /// assert_eq!(diff.changes[0].currency, Currency::USD);
+    /// assert!((diff.changes[0].percent + 9.09).abs() < 0.01);
+    /// ```
+    pub fn new(from: &convert::Response, to: &convert::Response) -> Result<Self> {
+        if from.base != to.base {
+            return Err(Error::DiffBaseMismatch {
+                from: from.base.clone(),
+                to: to.base.clone(),
+            });
+        }
+
+        let normalise = |resp: &convert::Response| {
+            resp.rates
+                .iter()
+                .map(|(c, v)| (c.clone(), **v / *resp.amount))
+                .collect::<BTreeMap<Currency, f64>>()
+        };
+        let (from_rates, mut to_rates) = (normalise(from), normalise(to));
+
+        let mut changes = Vec::new();
+        let mut disappeared = BTreeMap::new();
+        for (currency, from_rate) in from_rates {
+            match to_rates.remove(&currency) {
+                Some(to_rate) => changes.push(Change {
+                    currency,
+                    from: from_rate,
+                    to: to_rate,
+                    absolute: to_rate - from_rate,
+                    percent: (to_rate - from_rate) / from_rate * 100.0,
+                }),
+                None => {
+                    disappeared.insert(currency, from_rate);
+                }
+            }
+        }
+        changes.sort_by(|a, b| b.percent.abs().total_cmp(&a.percent.abs()));
+
+        Ok(Self {
+            base: from.base.clone(),
+            from_date: from.date,
+            to_date: to.date,
+            changes,
+            appeared: to_rates,
+            disappeared,
+        })
+    }
+}
+
+impl ServerClient {
+    /// Request the exchange rates for two dates, and compare them, see [`Diff::new`].
+    ///
+    /// Any date set on the request is ignored. If `to` is [`None`], the latest rates are used.
+    pub async fn diff(
+        &self,
+        req: convert::Request,
+        from: ValidDate,
+        to: Option<ValidDate>,
+    ) -> Result<Diff> {
+        let from = self
+            .convert(convert::Request {
+                date: Some(from),
+                ..req.clone()
+            })
+            .await?;
+        let to = self.convert(convert::Request { date: to, ..req }).await?;
+
+        Diff::new(&from, &to)
+    }
+}
+
+#[cfg(test)]
+mod tests_diff {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn response(base: &str, amount: f64, date: &str, rates: &[(&str, f64)]) -> convert::Response {
+        serde_json::from_value(serde_json::json!({
+            "base": base,
+            "amount": amount,
+            "date": date,
+            "rates": rates.iter().copied().collect::<BTreeMap<&str, f64>>(),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_diff() {
+        let from = response(
+            "EUR",
+            1.0,
+            "2024-01-31",
+            &[("GBP", 0.8), ("USD", 1.0), ("JPY", 150.0), ("ESP", 166.0)],
+        );
+        let to = response(
+            "EUR",
+            2.0,
+            "2024-02-29",
+            &[("GBP", 1.62), ("USD", 2.4), ("JPY", 300.0), ("BGN", 3.9)],
+        );
+        let diff = Diff::new(&from, &to).unwrap();
+
+        assert_eq!(diff.base, Currency::EUR);
+        assert_eq!(diff.from_date, from.date);
+        assert_eq!(diff.to_date, to.date);
+
+        // Sorted by magnitude, with amounts normalised
+        assert_eq!(
+            diff.changes
+                .iter()
+                .map(|c| c.currency.to_string())
+                .collect::<Vec<_>>(),
+            vec!["USD", "GBP", "JPY"]
+        );
+        let usd = &diff.changes[0];
+        assert_eq!((usd.from, usd.to), (1.0, 1.2));
+        assert!((usd.absolute - 0.2).abs() < 1e-9);
+        assert!((usd.percent - 20.0).abs() < 1e-9);
+        assert_eq!(diff.changes[2].percent, 0.0);
+
+        // Appeared and disappeared
+        assert_eq!(diff.appeared, BTreeMap::from([(Currency::BGN, 1.95)]));
+        assert_eq!(
+            diff.disappeared,
+            BTreeMap::from([(Currency::Other(String::from("ESP")), 166.0)])
+        );
+
+        // Mismatched base
+        let to = response("USD", 1.0, "2024-02-29", &[("EUR", 0.9)]);
+        assert!(matches!(
+            Diff::new(&from, &to),
+            Err(Error::DiffBaseMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,143 @@
+//! Health tracking for the base URLs of a [`super::ServerClient`], used to fail over between
+//! mirrors of the API.
+
+use std::{sync::Mutex, time::Duration};
+
+use url::Url;
+
+use super::RawResponse;
+use crate::{error::Result, time::Instant};
+
+/// How long an endpoint is skipped for after a failed request, by default.
+pub(super) const DEFAULT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// A base URL of the API, along with its health.
+#[derive(Debug)]
+pub(super) struct Endpoint {
+    pub(super) url: Url,
+    /// Time until which the endpoint should be skipped, if it has recently failed.
+    unhealthy_until: Mutex<Option<Instant>>,
+}
+
+impl Endpoint {
+    pub(super) fn new(url: Url) -> Self {
+        Self {
+            url,
+            unhealthy_until: Mutex::new(None),
+        }
+    }
+
+    /// Returns false if the endpoint has failed within its cooldown period.
+    pub(super) fn is_healthy(&self) -> bool {
+        self.unhealthy_until
+            .lock()
+            .expect("Endpoint health lock poisoned")
+            .is_none_or(|until| Instant::now() >= until)
+    }
+
+    /// Skip the endpoint until the given cooldown period has passed.
+    pub(super) fn mark_unhealthy(&self, cooldown: Duration) {
+        *self
+            .unhealthy_until
+            .lock()
+            .expect("Endpoint health lock poisoned") = Some(Instant::now() + cooldown);
+    }
+
+    pub(super) fn mark_healthy(&self) {
+        *self
+            .unhealthy_until
+            .lock()
+            .expect("Endpoint health lock poisoned") = None;
+    }
+}
+
+/// Order the endpoints by priority, moving any unhealthy ones to the end so that they are only
+/// used as a last resort.
+pub(super) fn prioritise(endpoints: &[Endpoint]) -> impl Iterator<Item = &Endpoint> {
+    let (healthy, unhealthy): (Vec<_>, Vec<_>) = endpoints.iter().partition(|e| e.is_healthy());
+    healthy.into_iter().chain(unhealthy)
+}
+
+/// Returns true if the result of a request indicates that the endpoint is down, meaning that the
+/// request should be retried with the next endpoint.
+pub(super) fn should_fail_over(result: &Result<RawResponse>) -> bool {
+    match result {
+        Ok(resp) => resp.status.is_server_error(),
+        Err(e) => e.is_network(),
+    }
+}
+
+#[cfg(test)]
+mod tests_failover {
+    use pretty_assertions::assert_eq;
+    use reqwest::StatusCode;
+
+    use super::*;
+    use crate::error::Error;
+
+    fn endpoints() -> Vec<Endpoint> {
+        ["http://a", "http://b", "http://c"]
+            .into_iter()
+            .map(|u| Endpoint::new(Url::parse(u).unwrap()))
+            .collect()
+    }
+
+    fn hosts<'a>(endpoints: impl Iterator<Item = &'a Endpoint>) -> Vec<&'a str> {
+        endpoints.map(|e| e.url.host_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_endpoint_health() {
+        let endpoint = Endpoint::new(Url::parse("http://a").unwrap());
+        assert!(endpoint.is_healthy());
+
+        endpoint.mark_unhealthy(Duration::from_secs(60));
+        assert!(!endpoint.is_healthy());
+
+        endpoint.mark_healthy();
+        assert!(endpoint.is_healthy());
+
+        // Cooldown has passed
+        endpoint.mark_unhealthy(Duration::ZERO);
+        assert!(endpoint.is_healthy());
+    }
+
+    #[test]
+    fn test_prioritise() {
+        let endpoints = endpoints();
+        assert_eq!(hosts(prioritise(&endpoints)), vec!["a", "b", "c"]);
+
+        endpoints[0].mark_unhealthy(DEFAULT_COOLDOWN);
+        assert_eq!(hosts(prioritise(&endpoints)), vec!["b", "c", "a"]);
+
+        endpoints[2].mark_unhealthy(DEFAULT_COOLDOWN);
+        assert_eq!(hosts(prioritise(&endpoints)), vec!["b", "a", "c"]);
+
+        endpoints[0].mark_healthy();
+        assert_eq!(hosts(prioritise(&endpoints)), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_should_fail_over() {
+        let response = |status| {
+            Ok(RawResponse {
+                url: Url::parse("http://a").unwrap(),
+                status,
+                body: Vec::new(),
+            })
+        };
+
+        assert!(should_fail_over(&response(
+            StatusCode::INTERNAL_SERVER_ERROR
+        )));
+        assert!(should_fail_over(&response(StatusCode::BAD_GATEWAY)));
+        assert!(!should_fail_over(&response(StatusCode::OK)));
+        assert!(!should_fail_over(&response(StatusCode::NOT_FOUND)));
+        assert!(!should_fail_over(&response(StatusCode::TOO_MANY_REQUESTS)));
+
+        assert!(should_fail_over(&Err(Error::from(std::io::Error::from(
+            std::io::ErrorKind::ConnectionRefused
+        )))));
+        assert!(!should_fail_over(&Err(Error::InvalidDate(String::new()))));
+    }
+}
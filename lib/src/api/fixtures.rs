@@ -0,0 +1,180 @@
+//! Record and replay responses from the API as fixtures, for deterministic tests which don't need a
+//! running API, see [`ServerClient::with_fixtures`].
+//!
+//! Each fixture is a JSON file in the fixtures directory, named after the path and query of the
+//! request. The host of the API is ignored, so fixtures recorded from one API can be replayed for
+//! any other.
+//!
+//! # Example
+//! ```no_run
+//! # use lib_frankfurter::api::{ServerClient, fixtures::FixtureMode};
+//! // Replay fixtures if `FRANKFURTER_FIXTURES=replay` is set, or record them if it is `record`
+//! let server_client = match FixtureMode::from_env() {
+//!     Some(mode) => ServerClient::default().with_fixtures("tests/fixtures", mode),
+//!     None => ServerClient::default(),
+//! };
+//! ```
+
+use std::path::PathBuf;
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::error::{Error, Result};
+
+/// Environment variable used by [`FixtureMode::from_env`].
+pub const ENV_VAR: &str = "FRANKFURTER_FIXTURES";
+
+/// Whether to record or replay fixtures, see [`ServerClient::with_fixtures`].
+///
+/// [`ServerClient::with_fixtures`]: super::ServerClient::with_fixtures
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FixtureMode {
+    /// Send requests to the API as usual, saving every response as a fixture.
+    Record,
+    /// Answer requests from the saved fixtures without sending them, failing with
+    /// [`Error::MissingFixture`] for any request which wasn't recorded.
+    Replay,
+}
+
+impl FixtureMode {
+    /// Get the mode from the [`ENV_VAR`] environment variable, which can be set to `record` or
+    /// `replay`. Returns [`None`] if it is unset or set to anything else.
+    pub fn from_env() -> Option<Self> {
+        match std::env::var(ENV_VAR).ok()?.to_lowercase().as_str() {
+            "record" => Some(Self::Record),
+            "replay" => Some(Self::Replay),
+            _ => None,
+        }
+    }
+}
+
+/// Fixtures directory and mode of a [`super::ServerClient`].
+#[derive(Clone, Debug)]
+pub(super) struct Fixtures {
+    pub(super) dir: PathBuf,
+    pub(super) mode: FixtureMode,
+}
+
+/// Contents of a fixture file.
+#[derive(Deserialize, Serialize)]
+struct Fixture {
+    /// Path and query of the request.
+    request: String,
+    status: u16,
+    body: String,
+}
+
+impl Fixtures {
+    /// Get the path and query of a request URL, which identifies its fixture.
+    fn request_key(url: &Url) -> String {
+        match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_owned(),
+        }
+    }
+
+    /// Get the path of the fixture for the given request URL.
+    ///
+    /// The file name is the path and query with any special characters replaced, followed by a
+    /// hash of the original path and query to avoid collisions.
+    fn path(&self, url: &Url) -> PathBuf {
+        let key = Self::request_key(url);
+        let name = key
+            .trim_start_matches('/')
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+
+        self.dir.join(format!("{name}-{:016x}.json", fnv1a(&key)))
+    }
+
+    /// Load the recorded response for the given request URL.
+    pub(super) fn load(&self, url: &Url) -> Result<(StatusCode, Vec<u8>)> {
+        let path = self.path(url);
+        let contents = std::fs::read(&path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => Error::MissingFixture {
+                request: Self::request_key(url),
+                path: path.clone(),
+            },
+            _ => e.into(),
+        })?;
+        let fixture: Fixture = serde_json::from_slice(&contents)?;
+
+        Ok((
+            StatusCode::from_u16(fixture.status).map_err(|_| Error::InvalidFixture(path))?,
+            fixture.body.into_bytes(),
+        ))
+    }
+
+    /// Save the response for the given request URL, overwriting any existing fixture.
+    pub(super) fn save(&self, url: &Url, status: StatusCode, body: &[u8]) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+
+        let fixture = Fixture {
+            request: Self::request_key(url),
+            status: status.as_u16(),
+            body: String::from_utf8_lossy(body).into_owned(),
+        };
+        let file = std::fs::File::create(self.path(url))?;
+        Ok(serde_json::to_writer_pretty(file, &fixture)?)
+    }
+}
+
+/// 64-bit FNV-1a hash, used as it is stable across platforms and Rust versions, unlike
+/// [`std::hash::DefaultHasher`].
+fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests_fixtures {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_path() {
+        let fixtures = Fixtures {
+            dir: PathBuf::from("fixtures"),
+            mode: FixtureMode::Replay,
+        };
+        let path = |url: &str| fixtures.path(&Url::parse(url).unwrap());
+
+        assert_eq!(
+            path("http://localhost:8080/v1/latest?base=USD&symbols=EUR,GBP"),
+            PathBuf::from("fixtures/v1_latest_base_USD_symbols_EUR_GBP-0f0f787d6629ae89.json")
+        );
+
+        // The host is ignored
+        assert_eq!(
+            path("http://localhost:8080/v1/2024-01-01..2024-01-05"),
+            path("https://api.frankfurter.dev/v1/2024-01-01..2024-01-05")
+        );
+
+        // Paths which only differ in special characters don't collide
+        assert_ne!(
+            path("http://localhost/v1/2024-01-01..2024-01-05"),
+            path("http://localhost/v1/2024-01-01__2024-01-05")
+        );
+        assert_ne!(
+            path("http://localhost/v1/latest"),
+            path("http://localhost/v1/latest?")
+        );
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a("a"), 0xaf63dc4c8601ec8c);
+    }
+}
@@ -0,0 +1,135 @@
+//! [`Health`] report on the availability and freshness of the data of a Frankfurter API.
+
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use url::Url;
+
+use super::{ServerClient, convert, failover};
+use crate::{
+    calendar,
+    data::{Currency, ValidDate},
+    error::Error,
+    time::{self, Instant},
+};
+
+/// Report on the health of a Frankfurter API, see [`ServerClient::health`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Health {
+    /// Base URL of the API which was checked.
+    pub url: Url,
+    /// Whether the API responded to the request for the latest rates, even if with an error.
+    pub reachable: bool,
+    /// Time taken for the request for the latest rates.
+    pub latency: Duration,
+    /// Version of the API being used, taken from the base URL, e.g. `v1`.
+    pub api_version: Option<String>,
+    /// Date of the latest rates available from the API.
+    pub latest_date: Option<ValidDate>,
+    /// Date of the latest rates which are expected to have been published.
+    pub expected_date: NaiveDate,
+    /// Error encountered when requesting the latest rates.
+    pub error: Option<Error>,
+}
+
+impl Health {
+    /// Returns true if the latest available rates are older than the expected date, e.g. if the
+    /// API has stopped importing new rates.
+    pub fn is_stale(&self) -> bool {
+        self.latest_date
+            .is_none_or(|date| *date < self.expected_date)
+    }
+
+    /// Returns true if the API is reachable, responding successfully and up to date.
+    pub fn is_healthy(&self) -> bool {
+        self.reachable && self.error.is_none() && !self.is_stale()
+    }
+}
+
+impl ServerClient {
+    /// Check the health of the API, by requesting the latest rates and comparing their date to the
+    /// expected date of the latest published rates, see [`calendar::expected_latest_date`].
+    ///
+    /// Unlike [`ServerClient::is_server_available`], this also catches an API which is up but has
+    /// stopped importing new rates.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
+    pub async fn health(&self) -> Health {
+        let start = Instant::now();
+        let result = self
+            .convert(convert::Request::default().with_targets(vec![Currency::USD]))
+            .await;
+        let latency = start.elapsed();
+
+        // Endpoints which responded are prioritised, so this is the one which was last used
+        let url = failover::prioritise(&self.endpoints)
+            .next()
+            .expect("No endpoints configured for the ServerClient")
+            .url
+            .clone();
+        let api_version = url
+            .path_segments()
+            .and_then(|mut s| s.next_back())
+            .filter(|s| s.starts_with('v') && s[1..].parse::<u32>().is_ok())
+            .map(ToOwned::to_owned);
+
+        let (latest_date, error) = match result {
+            Ok(resp) => (Some(resp.date), None),
+            Err(e) => (None, Some(e)),
+        };
+
+        Health {
+            url,
+            reachable: !error.as_ref().is_some_and(Error::is_network),
+            latency,
+            api_version,
+            latest_date,
+            expected_date: calendar::expected_latest_date(time::now()),
+            error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_health {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn health(latest_date: Option<&str>, error: Option<Error>) -> Health {
+        Health {
+            url: Url::parse("http://localhost:8080/v1").unwrap(),
+            reachable: true,
+            latency: Duration::ZERO,
+            api_version: Some(String::from("v1")),
+            latest_date: latest_date.map(|d| ValidDate::from_str(d).unwrap()),
+            expected_date: NaiveDate::from_ymd_opt(2024, 1, 10).unwrap(),
+            error,
+        }
+    }
+
+    #[test]
+    fn test_health() {
+        // UP TO DATE
+        let h = health(Some("2024-01-10"), None);
+        assert!(!h.is_stale());
+        assert!(h.is_healthy());
+
+        // STALE
+        let h = health(Some("2024-01-09"), None);
+        assert!(h.is_stale());
+        assert!(!h.is_healthy());
+
+        // ERROR
+        let h = health(None, Some(Error::InvalidDate(String::new())));
+        assert!(h.is_stale());
+        assert!(!h.is_healthy());
+
+        // UNREACHABLE
+        let h = Health {
+            reachable: false,
+            ..health(Some("2024-01-10"), None)
+        };
+        assert!(!h.is_healthy());
+    }
+}
@@ -0,0 +1,69 @@
+//! Hooks for inspecting or modifying the requests made by a [`super::ServerClient`], and
+//! inspecting the responses before they are deserialized.
+//!
+//! # Example
+//! ```
+//! # use lib_frankfurter::{
+//! #     api::{ServerClient, middleware::OnRequest},
+//! #     reqwest::{Request, header::{AUTHORIZATION, HeaderValue}},
+//! # };
+//! // Add an authorization header to every request, e.g. for an API behind an auth proxy
+//! let server_client = ServerClient::default().with_middleware(OnRequest(|req: &mut Request| {
+//!     req.headers_mut()
+//!         .insert(AUTHORIZATION, HeaderValue::from_static("Bearer token"));
+//! }));
+//! ```
+
+use std::time::Duration;
+
+use reqwest::{StatusCode, header::HeaderMap};
+use url::Url;
+
+/// A hook which is called for every request made by a [`super::ServerClient`].
+///
+/// Both methods do nothing by default, so only the relevant one needs to be implemented.
+/// Middleware is called in the order in which it was registered.
+pub trait Middleware: Send + Sync {
+    /// Inspect or modify an outgoing request before it is sent, e.g. to add headers, or to
+    /// rewrite the URL using [`reqwest::Request::url_mut`].
+    fn on_request(&self, _request: &mut reqwest::Request) {}
+
+    /// Inspect a response before its body is deserialized, e.g. for logging or recording metrics.
+    ///
+    /// This is also called for responses with an error status code.
+    fn on_response(&self, _response: &ResponseInfo<'_>) {}
+}
+
+/// Details of a response received from the API, see [`Middleware::on_response`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ResponseInfo<'a> {
+    /// Final URL of the request, including the query parameters.
+    pub url: &'a Url,
+    pub status: StatusCode,
+    pub headers: &'a HeaderMap,
+    /// Raw body of the response.
+    pub body: &'a [u8],
+    /// Time taken between sending the request and receiving the full response.
+    pub latency: Duration,
+}
+
+/// [`Middleware`] which calls the wrapped closure for every outgoing request.
+#[derive(Clone, Copy, Debug)]
+pub struct OnRequest<F>(pub F);
+
+impl<F: Fn(&mut reqwest::Request) + Send + Sync> Middleware for OnRequest<F> {
+    fn on_request(&self, request: &mut reqwest::Request) {
+        (self.0)(request)
+    }
+}
+
+/// [`Middleware`] which calls the wrapped closure for every response.
+#[derive(Clone, Copy, Debug)]
+pub struct OnResponse<F>(pub F);
+
+impl<F: Fn(&ResponseInfo<'_>) + Send + Sync> Middleware for OnResponse<F> {
+    fn on_response(&self, response: &ResponseInfo<'_>) {
+        (self.0)(response)
+    }
+}
@@ -1,64 +1,139 @@
 //! Interface to the Frankfurter API.
 
+mod builder;
 pub mod convert;
 pub mod currencies;
+pub mod diff;
+mod failover;
+pub mod fixtures;
+pub mod health;
+pub mod middleware;
 pub mod period;
 mod shared;
+#[cfg(feature = "watch")]
+pub mod watch;
 
-use std::borrow::Cow;
+use std::{borrow::Cow, fmt::Debug, path::PathBuf, sync::Arc, time::Duration};
 
+pub(crate) use builder::DEFAULT_API_VERSION_PATH;
+pub use builder::ServerClientBuilder;
+use builder::build_base_url;
+use failover::Endpoint;
+use fixtures::{FixtureMode, Fixtures};
+use middleware::{Middleware, ResponseInfo};
+use reqwest::StatusCode;
 use shared::*;
 use url::Url;
 
-use crate::error::{Error, Result};
+use crate::{
+    error::{Error, Result},
+    time::Instant,
+    trace::{event, record},
+};
 
 /// A HTTP client for making requests to a Frankfurter API.
-#[derive(Debug)]
+///
+/// Multiple base URLs can be configured using [`ServerClientBuilder::fallback_url`], in which case
+/// requests fail over to the next URL whenever one is unreachable or responds with a server error.
 pub struct ServerClient {
-    url: Url,
+    /// Base URLs of the API, in order of priority.
+    endpoints: Vec<Endpoint>,
     /// Inner client to perform HTTP requests.
     client: reqwest::Client,
+    /// Hooks called for every request and response, see [`Middleware`].
+    middleware: Vec<Arc<dyn Middleware>>,
+    /// How long to skip an endpoint for after a failed request.
+    failover_cooldown: Duration,
+    /// Directory to record responses to or replay them from, see [`ServerClient::with_fixtures`].
+    fixtures: Option<Fixtures>,
 }
 
 impl Default for ServerClient {
     fn default() -> Self {
         Self {
-            url: Url::parse("https://api.frankfurter.dev/v1")
-                .expect("Invalid fallback Frankfurter API URL"),
+            endpoints: vec![Endpoint::new(
+                Url::parse("https://api.frankfurter.dev/v1")
+                    .expect("Invalid fallback Frankfurter API URL"),
+            )],
             client: Default::default(),
+            middleware: Vec::new(),
+            failover_cooldown: failover::DEFAULT_COOLDOWN,
+            fixtures: None,
         }
     }
 }
 
-impl ServerClient {
-    pub fn new(mut frankfurter_api_url: Url) -> Self {
-        // Remove any number of trailing `/`
-        while frankfurter_api_url.path().ends_with('/')
-            && frankfurter_api_url.path_segments().unwrap().count() != 1
-        {
-            frankfurter_api_url.path_segments_mut().unwrap().pop();
-        }
+impl Debug for ServerClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerClient")
+            .field("endpoints", &self.endpoints)
+            .field("client", &self.client)
+            .field("middleware", &self.middleware.len())
+            .field("failover_cooldown", &self.failover_cooldown)
+            .field("fixtures", &self.fixtures)
+            .finish()
+    }
+}
 
-        // Append `/v1` to use correct version of the API
-        frankfurter_api_url.path_segments_mut().unwrap().push("v1");
+/// A response received from the API, before its body is deserialized.
+struct RawResponse {
+    url: Url,
+    status: StatusCode,
+    body: Vec<u8>,
+}
 
+impl ServerClient {
+    /// Create a [`ServerClient`] for the Frankfurter API at the given URL, with the default options.
+    ///
+    /// The path for the version of the API (`/v1`) is appended to the URL, after removing any
+    /// trailing `/`. Use [`ServerClient::builder`] to configure this and other options.
+    pub fn new(frankfurter_api_url: Url) -> Self {
         Self {
-            url: frankfurter_api_url,
-            client: Default::default(),
+            endpoints: vec![Endpoint::new(build_base_url(
+                frankfurter_api_url,
+                Some(DEFAULT_API_VERSION_PATH),
+                true,
+            ))],
+            ..Default::default()
         }
     }
 
+    /// Create a [`ServerClientBuilder`] for the Frankfurter API at the given URL.
+    pub fn builder(frankfurter_api_url: Url) -> ServerClientBuilder {
+        ServerClientBuilder::new(frankfurter_api_url)
+    }
+
     /// Consumes an existing [`ServerClient`] and returns one with the given [`reqwest::Client`].
     pub fn with_client(mut self, client: reqwest::Client) -> Self {
         self.client = client;
         self
     }
 
-    /// Construct an HTTP URL with the base and the provided endpoint.
+    /// Consumes an existing [`ServerClient`] and returns one with the given [`Middleware`]
+    /// registered, after any existing middleware.
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Consumes an existing [`ServerClient`] and returns one which records responses to, or
+    /// replays them from, fixtures in the given directory, see [`fixtures`].
+    ///
+    /// In [`FixtureMode::Replay`] no requests are sent, and any request without a fixture fails
+    /// with [`Error::MissingFixture`].
+    pub fn with_fixtures(mut self, dir: impl Into<PathBuf>, mode: FixtureMode) -> Self {
+        self.fixtures = Some(Fixtures {
+            dir: dir.into(),
+            mode,
+        });
+        self
+    }
+
+    /// Construct an HTTP URL with the given base and the provided endpoint.
     #[inline]
     #[must_use]
-    fn build_endpoint(&self, endpoint: &str) -> Url {
-        let mut url = self.url.clone();
+    fn build_endpoint(base: &Url, endpoint: &str) -> Url {
+        let mut url = base.clone();
         url.path_segments_mut()
             .expect("Couldn't get path segments")
             .push(&endpoint.replace('/', ""));
@@ -68,53 +143,195 @@ impl ServerClient {
     /// Makes a basic request to the root of the API and returns true in the event of a successful response.
     ///
     /// Useful for a simple check that the API is up and successfully responding to requests.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                url = tracing::field::Empty,
+                status = tracing::field::Empty,
+                latency_ms = tracing::field::Empty,
+            ),
+            ret
+        )
+    )]
     pub async fn is_server_available(&self) -> bool {
-        let mut base_url = self.url.clone();
-        base_url.set_path("");
+        let start = Instant::now();
+        let resp = self
+            .send_with_failover(|base| {
+                let mut base_url = base.clone();
+                base_url.set_path("");
+                record!("url", %base_url);
+
+                Ok(reqwest::Request::new(reqwest::Method::GET, base_url))
+            })
+            .await;
+        record!("latency_ms", start.elapsed().as_millis() as u64);
+
+        match resp {
+            Ok(r) => {
+                record!("status", r.status.as_u16());
+                r.status.is_success()
+            }
+            Err(_e) => {
+                event!(warn, error = %_e, "Failed to reach the API");
+                false
+            }
+        }
+    }
+
+    /// Send a request built for each endpoint in order of priority, until one is reachable and
+    /// doesn't respond with a server error.
+    ///
+    /// Endpoints which fail are skipped for the configured cooldown period, unless all other
+    /// endpoints have also failed.
+    async fn send_with_failover(
+        &self,
+        build_request: impl Fn(&Url) -> Result<reqwest::Request>,
+    ) -> Result<RawResponse> {
+        let mut last_result = None;
+
+        for endpoint in failover::prioritise(&self.endpoints) {
+            if !endpoint.is_healthy() {
+                event!(
+                    debug,
+                    endpoint = %endpoint.url,
+                    "All healthy endpoints failed, trying an unhealthy endpoint"
+                );
+            }
+
+            let result = self.send(build_request(&endpoint.url)?).await;
+            if !failover::should_fail_over(&result) {
+                endpoint.mark_healthy();
+                return result;
+            }
+
+            event!(
+                warn,
+                endpoint = %endpoint.url,
+                cooldown_secs = self.failover_cooldown.as_secs(),
+                "Request failed, skipping the endpoint during the cooldown"
+            );
+            endpoint.mark_unhealthy(self.failover_cooldown);
+            last_result = Some(result);
+        }
+
+        last_result.expect("No endpoints configured for the ServerClient")
+    }
 
-        self.client
-            .get(base_url)
-            .send()
-            .await
-            .is_ok_and(|r| r.status().is_success())
+    /// Send a request, passing it and its response through any registered [`Middleware`].
+    ///
+    /// The response is replayed from or recorded to the fixtures, if configured.
+    async fn send(&self, mut request: reqwest::Request) -> Result<RawResponse> {
+        for m in &self.middleware {
+            m.on_request(&mut request);
+        }
+
+        let start = Instant::now();
+        let (url, status, headers, body) = match &self.fixtures {
+            Some(fixtures) if fixtures.mode == FixtureMode::Replay => {
+                let (status, body) = fixtures.load(request.url())?;
+                (request.url().clone(), status, Default::default(), body)
+            }
+            _ => {
+                // Saved under the requested URL rather than any it was redirected to, which is the
+                // URL looked up on replay
+                let request_url = request.url().clone();
+                let resp = self.client.execute(request).await?;
+                let url = resp.url().clone();
+                let status = resp.status();
+                let headers = resp.headers().clone();
+                let body = Vec::from(resp.bytes().await?);
+
+                if let Some(fixtures) = &self.fixtures {
+                    fixtures.save(&request_url, status, &body)?;
+                }
+                (url, status, headers, body)
+            }
+        };
+
+        let info = ResponseInfo {
+            url: &url,
+            status,
+            headers: &headers,
+            body: &body,
+            latency: start.elapsed(),
+        };
+        for m in &self.middleware {
+            m.on_response(&info);
+        }
+
+        Ok(RawResponse { url, status, body })
     }
 
     /// Internal method for handling `GET` requests.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                endpoint = tracing::field::Empty,
+                query = tracing::field::Empty,
+                status = tracing::field::Empty,
+                latency_ms = tracing::field::Empty,
+                response_size = tracing::field::Empty,
+            ),
+            err(Display)
+        )
+    )]
     async fn get<Resp: for<'de> serde::Deserialize<'de>>(
         &self,
         req: impl ServerClientRequest,
     ) -> Result<Resp> {
         let (endpoint, params) = req.setup()?;
+        record!("query", ?params);
+
+        let start = Instant::now();
         let resp = self
-            .client
-            .get(self.build_endpoint(&endpoint))
-            .query(&params)
-            .send()
+            .send_with_failover(|base| {
+                let url = Self::build_endpoint(base, &endpoint);
+                record!("endpoint", %url);
+
+                self.client
+                    .get(url)
+                    .query(&params)
+                    .build()
+                    .map_err(Into::into)
+            })
             .await?;
+        record!("latency_ms", start.elapsed().as_millis() as u64);
+        record!("status", resp.status.as_u16());
+        record!("response_size", resp.body.len() as u64);
 
         // Return an error in the case of a response with an error status code from the API
-        if let Err(err) = resp.error_for_status_ref() {
-            return Err(Error::InvalidResponse {
-                status: err.status().expect("Couldn't get status from response"),
-                body: resp.text().await.unwrap_or_default(),
-                url: self.build_endpoint(&endpoint).to_string(),
-            });
+        if resp.status.is_client_error() || resp.status.is_server_error() {
+            return Err(Error::invalid_response(
+                &resp.url,
+                resp.status,
+                String::from_utf8_lossy(&resp.body).into_owned(),
+            ));
         };
 
-        resp.json::<Resp>().await.map_err(Into::into)
+        event!(debug, "Received response from the API");
+        serde_json::from_slice::<Resp>(&resp.body).map_err(Into::into)
     }
 
     /// Request exchange rates for a specific date (latest by default).
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     pub async fn convert(&self, req: convert::Request) -> Result<convert::Response> {
         self.get::<convert::Response>(req).await
     }
 
     /// Request historical exchange rates for a given time period.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     pub async fn period(&self, req: period::Request) -> Result<period::Response> {
         self.get::<period::Response>(req).await
     }
 
     /// Request the latest supported currency codes and their full names.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip(self)))]
     pub async fn currencies(&self, req: currencies::Request) -> Result<currencies::Response> {
         self.get::<currencies::Response>(req).await
     }
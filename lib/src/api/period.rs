@@ -1,14 +1,17 @@
 //! [`Request`] and [`Response`] types for requesting historical exchange rates for a
 //! given time period.
 
-use std::{borrow::Cow, collections::BTreeMap};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+};
 
 use serde::{Deserialize, Serialize};
 
 use super::{ServerClientRequest, base_build_query_params, base_ensure_valid};
 use crate::{
     ValidDate,
-    data::{Currency, CurrencyValue, CurrencyValueMap},
+    data::{Currency, CurrencyValue, CurrencyValueMap, Quotation},
     error::Error,
 };
 
@@ -27,6 +30,70 @@ pub struct Response {
     pub rates: BTreeMap<ValidDate, CurrencyValueMap>,
 }
 
+/// Exchange rates of a [`Response`] in a wide layout, with a row per date and a column per
+/// currency, see [`Response::pivot`].
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct Pivot {
+    /// The ISO 4217 code of the base currency being compared
+    pub base: Currency,
+    /// Amount of the base currency being compared
+    pub amount: CurrencyValue,
+    /// Currencies of the columns, sorted by their codes
+    pub currencies: Vec<Currency>,
+    /// Dates and their values for each of the [`Pivot::currencies`], or [`None`] if there was no
+    /// rate for the currency on that date
+    pub rows: Vec<(ValidDate, Vec<Option<CurrencyValue>>)>,
+}
+
+impl Response {
+    /// Get the rates in a wide layout, with a row per date and a column per currency.
+    pub fn pivot(&self) -> Pivot {
+        let currencies: Vec<Currency> = self
+            .rates
+            .values()
+            .flat_map(BTreeMap::keys)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .cloned()
+            .collect();
+
+        Pivot {
+            base: self.base.clone(),
+            amount: self.amount,
+            rows: self
+                .rates
+                .iter()
+                .map(|(date, rates)| {
+                    (
+                        *date,
+                        currencies.iter().map(|c| rates.get(c).copied()).collect(),
+                    )
+                })
+                .collect(),
+            currencies,
+        }
+    }
+
+    /// Get the rates for each date and target currency using the given [`Quotation`], see
+    /// [`super::convert::Response::quote`].
+    pub fn quote(&self, quotation: Quotation) -> BTreeMap<ValidDate, BTreeMap<Currency, f64>> {
+        self.rates
+            .iter()
+            .map(|(date, rates)| {
+                (
+                    *date,
+                    rates
+                        .iter()
+                        .map(|(currency, value)| {
+                            (currency.clone(), quotation.rate(*self.amount, **value))
+                        })
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+}
+
 /// Request query parameters for fetching the latest exchange rates.
 #[derive(Clone, PartialEq, Debug, Serialize, Default)]
 pub struct Request {
@@ -107,6 +174,50 @@ mod tests_period {
     use super::*;
     use crate::api::test_utils::dbg_err;
 
+    #[test]
+    fn test_pivot() {
+        let resp: Response = serde_json::from_str(
+            r#"{"base": "EUR", "amount": 1.0, "start_date": "2024-01-08", "end_date": "2024-01-09", "rates": {"2024-01-08": {"GBP": 0.86, "USD": 1.09}, "2024-01-09": {"JPY": 160.0, "USD": 1.1}}}"#,
+        )
+        .unwrap();
+        let date = |s: &str| ValidDate::from_str(s).unwrap();
+        let value = |v: f64| Some(CurrencyValue::try_from(v).unwrap());
+
+        let pivot = resp.pivot();
+        assert_eq!(
+            pivot.currencies,
+            vec![Currency::GBP, Currency::JPY, Currency::USD]
+        );
+        assert_eq!(
+            pivot.rows,
+            vec![
+                (date("2024-01-08"), vec![value(0.86), None, value(1.09)]),
+                (date("2024-01-09"), vec![None, value(160.0), value(1.1)]),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(&pivot).unwrap()["rows"][1],
+            serde_json::json!(["2024-01-09", [null, 160.0, 1.1]])
+        );
+    }
+
+    #[test]
+    fn test_quote() {
+        let resp: Response = serde_json::from_str(
+            r#"{"base": "EUR", "amount": 2.0, "start_date": "2024-01-08", "end_date": "2024-01-09", "rates": {"2024-01-08": {"USD": 2.0}, "2024-01-09": {"USD": 2.5}}}"#,
+        )
+        .unwrap();
+        let date = |s: &str| ValidDate::from_str(s).unwrap();
+
+        assert_eq!(
+            resp.quote(Quotation::Indirect { per: 1 }),
+            BTreeMap::from([
+                (date("2024-01-08"), BTreeMap::from([(Currency::USD, 1.0)])),
+                (date("2024-01-09"), BTreeMap::from([(Currency::USD, 0.8)])),
+            ])
+        );
+    }
+
     #[test]
     fn get_url() {
         assert_eq!(
@@ -32,7 +32,7 @@ pub(super) fn base_build_query_params(
     let mut query_params = vec![];
 
     if let Some(a) = amount {
-        query_params.push(("amount", format!("{a:.2}")));
+        query_params.push(("amount", a.to_string()));
     };
 
     if let Some(b) = base {
@@ -96,6 +96,18 @@ mod tests_shared {
             base_build_query_params(&Some(CurrencyValue::try_from(10.0).unwrap()), &None, &None),
             vec![("amount", String::from("10.00"))]
         );
+        assert_eq!(
+            base_build_query_params(&Some(CurrencyValue::new(0.005, 3).unwrap()), &None, &None),
+            vec![("amount", String::from("0.005"))]
+        );
+        assert_eq!(
+            base_build_query_params(
+                &Some(CurrencyValue::new(1234.5678, 4).unwrap()),
+                &None,
+                &None
+            ),
+            vec![("amount", String::from("1234.5678"))]
+        );
         assert_eq!(
             base_build_query_params(&None, &Some(Currency::AUD), &None),
             vec![("base", String::from("AUD"))]
@@ -0,0 +1,163 @@
+//! [`Watch`] for polling the latest exchange rates, yielding new rates as soon as they are
+//! published.
+//!
+//! Polls are scheduled around the publication times of the reference rates (see [`calendar`]), so
+//! the API is only queried again once new rates are expected, and then retried until they appear.
+//!
+//! # Example
+//! ```no_run
+//! # use std::pin::pin;
+//! # use futures_util::StreamExt;
+//! # use lib_frankfurter::api::{ServerClient, convert};
+//! # async fn run() {
+//! let server_client = ServerClient::default();
+//! let mut rates = pin!(server_client.watch_latest(convert::Request::default()).into_stream());
+//!
+//! while let Some(result) = rates.next().await {
+//!     match result {
+//!         Ok(resp) => println!("New rates for {}", resp.date),
+//!         Err(e) => eprintln!("Failed to fetch the latest rates: {e}"),
+//!     }
+//! }
+//! # }
+//! ```
+
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use futures_util::{Stream, stream};
+
+use super::{ServerClient, convert};
+use crate::{calendar, error::Result};
+
+/// Default time to wait before polling again when new rates are due but not yet available, or
+/// when a request fails.
+pub const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Watcher for the latest exchange rates, see [`ServerClient::watch_latest`].
+#[derive(Debug)]
+pub struct Watch<'a> {
+    server_client: &'a ServerClient,
+    request: convert::Request,
+    retry_interval: Duration,
+}
+
+impl<'a> Watch<'a> {
+    /// Consumes the [`Watch`] and returns a new one with the given retry interval, used when new
+    /// rates are due but not yet available from the API, or when a request fails.
+    ///
+    /// Defaults to [`DEFAULT_RETRY_INTERVAL`].
+    pub fn with_retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self
+    }
+
+    /// Consumes the [`Watch`] and returns a [`Stream`] which never ends.
+    ///
+    /// The first item is the current latest rates, after which an item is only yielded when the
+    /// date of the rates changes. Failed requests are yielded as errors, and are retried after the
+    /// retry interval.
+    pub fn into_stream(self) -> impl Stream<Item = Result<convert::Response>> + 'a {
+        let Self {
+            server_client,
+            request,
+            retry_interval,
+        } = self;
+
+        stream::unfold(
+            (None::<NaiveDate>, Duration::ZERO),
+            move |(mut last_date, mut delay)| {
+                let request = request.clone();
+                async move {
+                    loop {
+                        tokio::time::sleep(delay).await;
+
+                        match server_client.convert(request.clone()).await {
+                            Ok(resp) => {
+                                let date = *resp.date;
+                                let is_new = last_date != Some(date);
+
+                                last_date = Some(date);
+                                delay = next_poll_delay(Utc::now(), date, retry_interval);
+
+                                if is_new {
+                                    return Some((Ok(resp), (last_date, delay)));
+                                }
+                            }
+                            Err(e) => return Some((Err(e), (last_date, retry_interval))),
+                        }
+                    }
+                }
+            },
+        )
+    }
+}
+
+impl ServerClient {
+    /// Watch the latest exchange rates for the given request, see [`Watch::into_stream`].
+    ///
+    /// Any date set on the request is ignored, as only the latest rates are watched.
+    pub fn watch_latest(&self, mut request: convert::Request) -> Watch<'_> {
+        request.date = None;
+        Watch {
+            server_client: self,
+            request,
+            retry_interval: DEFAULT_RETRY_INTERVAL,
+        }
+    }
+}
+
+/// Get the time to wait before polling again, given the date of the latest rates received.
+///
+/// If newer rates should already have been published, the retry interval is used, otherwise
+/// polling resumes at the next publication time.
+fn next_poll_delay(now: DateTime<Utc>, last_date: NaiveDate, retry_interval: Duration) -> Duration {
+    if calendar::expected_latest_date(now) > last_date {
+        return retry_interval;
+    }
+
+    (calendar::next_publication(now) - now)
+        .to_std()
+        .unwrap_or(retry_interval)
+}
+
+#[cfg(test)]
+mod tests_watch {
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_next_poll_delay() {
+        let retry = Duration::from_secs(60);
+        let date = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+
+        // UP TO DATE - wait for the next publication
+        let now = Utc.with_ymd_and_hms(2024, 1, 10, 9, 0, 0).unwrap();
+        assert_eq!(
+            next_poll_delay(now, date(9), retry),
+            Duration::from_secs(7 * 60 * 60)
+        );
+
+        let now = Utc.with_ymd_and_hms(2024, 1, 10, 17, 0, 0).unwrap();
+        assert_eq!(
+            next_poll_delay(now, date(10), retry),
+            Duration::from_secs(23 * 60 * 60)
+        );
+
+        // Over the weekend
+        let now = Utc.with_ymd_and_hms(2024, 1, 6, 16, 0, 0).unwrap();
+        assert_eq!(
+            next_poll_delay(now, date(5), retry),
+            Duration::from_secs(48 * 60 * 60)
+        );
+
+        // NEW RATES DUE - retry
+        let now = Utc.with_ymd_and_hms(2024, 1, 10, 16, 30, 0).unwrap();
+        assert_eq!(next_poll_delay(now, date(9), retry), retry);
+
+        let now = Utc.with_ymd_and_hms(2024, 1, 8, 9, 0, 0).unwrap();
+        assert_eq!(next_poll_delay(now, date(4), retry), retry);
+    }
+}
@@ -0,0 +1,632 @@
+//! [`Calculation`]s of arithmetic expressions over amounts of money in different currencies, e.g.
+//! `120 USD + 45.50 GBP - 10 CHF in EUR`, evaluated against the response of the [`convert`]
+//! endpoint.
+//!
+//! Amounts can be added and subtracted, multiplied and divided by plain numbers, and grouped with
+//! brackets. The result is in the currency given after `in` (or `to`), or the currency of the
+//! first amount by default.
+//!
+//! # Example
+//! ```no_run
+//! # use lib_frankfurter::{api::ServerClient, calc::Calculation};
+//! # async fn run() -> Result<(), lib_frankfurter::Error> {
+//! let server_client = ServerClient::default();
+//!
+//! let calculation: Calculation = "(120 USD + 45.50 GBP) / 3 in EUR".parse()?;
+//! let response = server_client.convert(calculation.convert_request(None)).await?;
+//!
+//! println!("{}", calculation.evaluate(&response)?.total);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{collections::BTreeSet, fmt::Display, iter::Peekable, str::FromStr};
+
+use serde::Serialize;
+
+use crate::{
+    api::convert,
+    data::{Currency, ValidDate},
+    error::{Error, Result},
+};
+
+// EXPRESSION --------------------------------------------------------------------------------------
+/// Arithmetic operator between two expressions.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Self::Add => '+',
+            Self::Subtract => '-',
+            Self::Multiply => '*',
+            Self::Divide => '/',
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+/// Parsed arithmetic expression over amounts of money and plain numbers.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Expression {
+    /// An amount of money in a currency, e.g. `45.50 GBP`.
+    Money { amount: f64, currency: Currency },
+    /// A plain number, e.g. to split a total with `/ 3`.
+    Number(f64),
+    /// The negation of an expression, e.g. `-10 CHF`.
+    Negate(Box<Expression>),
+    /// Two expressions combined by an operator.
+    Binary {
+        operator: Operator,
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Money { amount, currency } => write!(f, "{amount} {currency}"),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Negate(e) => write!(f, "-{e}"),
+            Self::Binary {
+                operator,
+                left,
+                right,
+            } => write!(f, "({left} {operator} {right})"),
+        }
+    }
+}
+
+/// Value of an evaluated expression, which is either an amount of money in the target currency
+/// or a plain number.
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Value {
+    amount: f64,
+    is_money: bool,
+}
+
+impl Expression {
+    /// Call the function on each amount of money in the expression, from left to right.
+    fn for_each_money(&self, f: &mut impl FnMut(f64, &Currency)) {
+        match self {
+            Self::Money { amount, currency } => f(*amount, currency),
+            Self::Number(_) => {}
+            Self::Negate(e) => e.for_each_money(f),
+            Self::Binary { left, right, .. } => {
+                left.for_each_money(f);
+                right.for_each_money(f);
+            }
+        }
+    }
+
+    /// Evaluate the expression, converting amounts of money with the given function.
+    fn evaluate(&self, convert: &impl Fn(f64, &Currency) -> Result<f64>) -> Result<Value> {
+        let invalid = |reason: &str| Error::InvalidCalculation(format!("{reason}, in '{self}'"));
+
+        match self {
+            Self::Money { amount, currency } => Ok(Value {
+                amount: convert(*amount, currency)?,
+                is_money: true,
+            }),
+            Self::Number(n) => Ok(Value {
+                amount: *n,
+                is_money: false,
+            }),
+            Self::Negate(e) => e.evaluate(convert).map(|v| Value {
+                amount: -v.amount,
+                ..v
+            }),
+            Self::Binary {
+                operator,
+                left,
+                right,
+            } => {
+                let (left, right) = (left.evaluate(convert)?, right.evaluate(convert)?);
+                let (amount, is_money) = match operator {
+                    Operator::Add | Operator::Subtract if left.is_money != right.is_money => {
+                        return Err(invalid("cannot add or subtract a number and an amount"));
+                    }
+                    Operator::Add => (left.amount + right.amount, left.is_money),
+                    Operator::Subtract => (left.amount - right.amount, left.is_money),
+                    Operator::Multiply if left.is_money && right.is_money => {
+                        return Err(invalid("cannot multiply two amounts"));
+                    }
+                    Operator::Multiply => {
+                        (left.amount * right.amount, left.is_money || right.is_money)
+                    }
+                    Operator::Divide if right.amount == 0.0 => {
+                        return Err(invalid("cannot divide by zero"));
+                    }
+                    Operator::Divide if right.is_money && !left.is_money => {
+                        return Err(invalid("cannot divide a number by an amount"));
+                    }
+                    // Dividing two amounts gives their ratio
+                    Operator::Divide => {
+                        (left.amount / right.amount, left.is_money != right.is_money)
+                    }
+                };
+
+                Ok(Value { amount, is_money })
+            }
+        }
+    }
+}
+
+// PARSER ------------------------------------------------------------------------------------------
+/// Token of an expression.
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Number(f64),
+    Word(String),
+    Operator(Operator),
+    Open,
+    Close,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(n) => write!(f, "'{n}'"),
+            Self::Word(w) => write!(f, "'{w}'"),
+            Self::Operator(o) => write!(f, "'{o}'"),
+            Self::Open => write!(f, "opening bracket"),
+            Self::Close => write!(f, "closing bracket"),
+        }
+    }
+}
+
+/// Split the expression into tokens.
+fn tokenize(s: &str) -> Result<Vec<Token>> {
+    let invalid = |reason: String| Error::InvalidCalculation(format!("{reason}, in '{s}'"));
+    let mut chars = s.chars().peekable();
+    let mut tokens = Vec::new();
+
+    while let Some(&c) = chars.peek() {
+        let token = match c {
+            _ if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '0'..='9' | '.' => {
+                let mut number = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || *c == '.' || *c == '_')
+                {
+                    number.push(c);
+                }
+                Token::Number(
+                    number
+                        .replace('_', "")
+                        .parse()
+                        .map_err(|_| invalid(format!("invalid number '{number}'")))?,
+                )
+            }
+            _ if c.is_alphabetic() => {
+                let mut word = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric()) {
+                    word.push(c);
+                }
+                Token::Word(word)
+            }
+            '+' | '-' | '*' | '/' | '×' | '÷' | '(' | ')' => {
+                chars.next();
+                match c {
+                    '+' => Token::Operator(Operator::Add),
+                    '-' => Token::Operator(Operator::Subtract),
+                    '*' | '×' => Token::Operator(Operator::Multiply),
+                    '/' | '÷' => Token::Operator(Operator::Divide),
+                    '(' => Token::Open,
+                    _ => Token::Close,
+                }
+            }
+            _ => return Err(invalid(format!("unexpected character '{c}'"))),
+        };
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent parser of expressions, with the usual precedence of operators.
+struct Parser<I: Iterator<Item = Token>> {
+    tokens: Peekable<I>,
+}
+
+impl<I: Iterator<Item = Token>> Parser<I> {
+    /// expression := term (('+' | '-') term)*
+    fn expression(&mut self) -> std::result::Result<Expression, String> {
+        let mut left = self.term()?;
+        while let Some(Token::Operator(operator @ (Operator::Add | Operator::Subtract))) =
+            self.tokens.peek().cloned()
+        {
+            self.tokens.next();
+            left = Expression::Binary {
+                operator,
+                left: Box::new(left),
+                right: Box::new(self.term()?),
+            };
+        }
+        Ok(left)
+    }
+
+    /// term := unary (('*' | '/') unary)*
+    fn term(&mut self) -> std::result::Result<Expression, String> {
+        let mut left = self.unary()?;
+        while let Some(Token::Operator(operator @ (Operator::Multiply | Operator::Divide))) =
+            self.tokens.peek().cloned()
+        {
+            self.tokens.next();
+            left = Expression::Binary {
+                operator,
+                left: Box::new(left),
+                right: Box::new(self.unary()?),
+            };
+        }
+        Ok(left)
+    }
+
+    /// unary := ('-' | '+') unary | primary
+    fn unary(&mut self) -> std::result::Result<Expression, String> {
+        match self.tokens.peek() {
+            Some(Token::Operator(Operator::Subtract)) => {
+                self.tokens.next();
+                Ok(Expression::Negate(Box::new(self.unary()?)))
+            }
+            Some(Token::Operator(Operator::Add)) => {
+                self.tokens.next();
+                self.unary()
+            }
+            _ => self.primary(),
+        }
+    }
+
+    /// primary := NUMBER CURRENCY? | CURRENCY NUMBER | '(' expression ')'
+    fn primary(&mut self) -> std::result::Result<Expression, String> {
+        match self.tokens.next() {
+            Some(Token::Number(amount)) => match self.tokens.peek() {
+                Some(Token::Word(word)) if !is_target_keyword(word) => {
+                    let currency = word
+                        .parse::<Currency>()
+                        .map_err(|_| format!("invalid currency '{word}'"))?;
+                    self.tokens.next();
+                    Ok(Expression::Money { amount, currency })
+                }
+                _ => Ok(Expression::Number(amount)),
+            },
+            Some(Token::Word(word)) => {
+                let currency = word
+                    .parse::<Currency>()
+                    .map_err(|_| format!("invalid currency '{word}'"))?;
+                match self.tokens.next() {
+                    Some(Token::Number(amount)) => Ok(Expression::Money { amount, currency }),
+                    _ => Err(format!("expected an amount after '{word}'")),
+                }
+            }
+            Some(Token::Open) => {
+                let expression = self.expression()?;
+                match self.tokens.next() {
+                    Some(Token::Close) => Ok(expression),
+                    _ => Err(String::from("missing closing bracket")),
+                }
+            }
+            Some(Token::Operator(operator)) => Err(format!("unexpected '{operator}'")),
+            Some(Token::Close) => Err(String::from("unexpected closing bracket")),
+            None => Err(String::from("unexpected end of expression")),
+        }
+    }
+}
+
+/// Whether the word introduces the target currency, e.g. `in EUR`.
+fn is_target_keyword(word: &str) -> bool {
+    word.eq_ignore_ascii_case("in") || word.eq_ignore_ascii_case("to")
+}
+
+// CALCULATION -------------------------------------------------------------------------------------
+/// An expression over amounts of money, with the currency to give its result in.
+///
+/// # Example
+/// ```
+/// # use lib_frankfurter::{Currency, calc::Calculation};
+/// let calculation: Calculation = "120 USD + 45.50 GBP - 10 CHF in EUR".parse().unwrap();
+/// assert_eq!(calculation.target, Currency::EUR);
+/// assert_eq!(
+///     calculation.currencies().into_iter().collect::<Vec<_>>(),
+///     [Currency::CHF, Currency::GBP, Currency::USD]
+/// );
+///
+/// // Defaults to the currency of the first amount
+/// let calculation: Calculation = "3 * 9.99 usd + 5 eur".parse().unwrap();
+/// assert_eq!(calculation.target, Currency::USD);
+///
+/// assert!("120 USD +".parse::<Calculation>().is_err());
+/// assert!("120 USD + 5".parse::<Calculation>().is_ok());
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct Calculation {
+    pub expression: Expression,
+    /// Currency to give the result in.
+    pub target: Currency,
+}
+
+impl FromStr for Calculation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: String| Error::InvalidCalculation(format!("{reason}, in '{s}'"));
+        let mut parser = Parser {
+            tokens: tokenize(s)?.into_iter().peekable(),
+        };
+
+        let expression = parser.expression().map_err(invalid)?;
+        let target = match parser.tokens.next() {
+            Some(Token::Word(word)) if is_target_keyword(&word) => {
+                match (parser.tokens.next(), parser.tokens.next()) {
+                    (Some(Token::Word(target)), None) => target
+                        .parse::<Currency>()
+                        .map_err(|_| invalid(format!("invalid currency '{target}'")))?,
+                    _ => return Err(invalid(format!("expected a currency after '{word}'"))),
+                }
+            }
+            Some(token) => return Err(invalid(format!("unexpected {token}"))),
+            None => {
+                let mut first = None;
+                expression.for_each_money(&mut |_, c| {
+                    first.get_or_insert_with(|| c.clone());
+                });
+                first.ok_or_else(|| invalid(String::from("no amounts of money")))?
+            }
+        };
+
+        Ok(Self { expression, target })
+    }
+}
+
+/// An amount of money in an expression, converted to the target currency.
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct Term {
+    pub amount: f64,
+    pub currency: Currency,
+    /// Amount of the currency for one unit of the target currency.
+    pub rate: f64,
+    pub converted: f64,
+}
+
+/// Result of evaluating a [`Calculation`], with a breakdown of the amounts in it.
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct Evaluation {
+    pub target: Currency,
+    /// Date of the exchange rates used.
+    pub date: ValidDate,
+    pub total: f64,
+    /// Amounts of money in the expression, from left to right.
+    pub terms: Vec<Term>,
+}
+
+impl Calculation {
+    /// Get the currencies of the amounts in the expression, other than the target currency.
+    pub fn currencies(&self) -> BTreeSet<Currency> {
+        let mut currencies = BTreeSet::new();
+        self.expression.for_each_money(&mut |_, c| {
+            if *c != self.target {
+                currencies.insert(c.clone());
+            }
+        });
+        currencies
+    }
+
+    /// Get the request for the rates needed to evaluate the calculation, on the given date or the
+    /// latest one.
+    pub fn convert_request(&self, date: Option<ValidDate>) -> convert::Request {
+        convert::Request {
+            base: Some(self.target.clone()),
+            targets: Some(self.currencies().into_iter().collect()),
+            amount: None,
+            date,
+        }
+    }
+
+    /// Evaluate the calculation with the rates of a response to [`Calculation::convert_request`].
+    pub fn evaluate(&self, response: &convert::Response) -> Result<Evaluation> {
+        let rate = |currency: &Currency| -> Result<f64> {
+            if *currency == self.target {
+                return Ok(1.0);
+            }
+            if response.base != self.target {
+                return Err(Error::InvalidCalculation(format!(
+                    "rates are for {}, not {}",
+                    response.base, self.target
+                )));
+            }
+            response
+                .rates
+                .get(currency)
+                .map(|v| **v / *response.amount)
+                .ok_or_else(|| {
+                    Error::InvalidCalculation(format!(
+                        "no exchange rate for {currency} on {}",
+                        response.date
+                    ))
+                })
+        };
+
+        let value = self
+            .expression
+            .evaluate(&|amount, c| Ok(amount / rate(c)?))?;
+        if !value.is_money {
+            return Err(Error::InvalidCalculation(format!(
+                "the result must be an amount of money, in '{}'",
+                self.expression
+            )));
+        }
+
+        let mut terms = Vec::new();
+        let mut missing = None;
+        self.expression
+            .for_each_money(&mut |amount, currency| match rate(currency) {
+                Ok(rate) => terms.push(Term {
+                    amount,
+                    currency: currency.clone(),
+                    rate,
+                    converted: amount / rate,
+                }),
+                Err(e) => {
+                    missing.get_or_insert(e);
+                }
+            });
+        if let Some(e) = missing {
+            return Err(e);
+        }
+
+        Ok(Evaluation {
+            target: self.target.clone(),
+            date: response.date,
+            total: value.amount,
+            terms,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests_calc {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn money(amount: f64, currency: Currency) -> Box<Expression> {
+        Box::new(Expression::Money { amount, currency })
+    }
+
+    fn response() -> convert::Response {
+        serde_json::from_str(
+            r#"{"base": "EUR", "amount": 1.0, "date": "2024-01-09", "rates": {"CHF": 0.5, "GBP": 0.8, "USD": 1.25}}"#,
+        )
+        .unwrap()
+    }
+
+    fn total(s: &str) -> Result<f64> {
+        s.parse::<Calculation>()?
+            .evaluate(&response())
+            .map(|e| e.total)
+    }
+
+    #[test]
+    fn test_parse() {
+        let calculation: Calculation = "120 USD + 45.50 gbp - 10 CHF in EUR".parse().unwrap();
+
+        assert_eq!(
+            calculation.expression,
+            Expression::Binary {
+                operator: Operator::Subtract,
+                left: Box::new(Expression::Binary {
+                    operator: Operator::Add,
+                    left: money(120.0, Currency::USD),
+                    right: money(45.5, Currency::GBP),
+                }),
+                right: money(10.0, Currency::CHF),
+            }
+        );
+        assert_eq!(calculation.target, Currency::EUR);
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        let calculation: Calculation = "-USD 10 + 2 * (3 GBP - 1 GBP) / 4 to CHF".parse().unwrap();
+
+        assert_eq!(
+            calculation.expression.to_string(),
+            "(-10 USD + ((2 * (3 GBP - 1 GBP)) / 4))"
+        );
+        assert_eq!(calculation.target, Currency::CHF);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in [
+            "",
+            "120",
+            "120 USD +",
+            "120 USD 5",
+            "120 US",
+            "(120 USD",
+            "120 USD)",
+            "120 USD in",
+            "120 USD in EUR GBP",
+            "120 USD & 5 GBP",
+            "1.2.3 USD",
+        ] {
+            assert!(
+                matches!(s.parse::<Calculation>(), Err(Error::InvalidCalculation(_))),
+                "{s}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_currencies() {
+        let calculation: Calculation = "1 USD + 2 EUR + 3 USD + 4 GBP in EUR".parse().unwrap();
+
+        assert_eq!(
+            calculation.convert_request(None).targets,
+            Some(vec![Currency::GBP, Currency::USD])
+        );
+    }
+
+    #[test]
+    fn test_evaluate() {
+        // 100 + 50 - 20
+        assert_eq!(total("125 USD + 40 GBP - 10 CHF in EUR").unwrap(), 130.0);
+        // 2 * 50 + 8 / 2 * 2
+        assert_eq!(total("2 * 40 GBP + 10 USD / 2 * 2 in EUR").unwrap(), 108.0);
+        assert_eq!(total("-(1 EUR - 3 EUR)").unwrap(), 2.0);
+        // Ratios of amounts can scale other amounts
+        assert_eq!(total("(2 GBP / 1 CHF) * 10 EUR in EUR").unwrap(), 12.5);
+    }
+
+    #[test]
+    fn test_evaluate_invalid() {
+        for s in [
+            "1 EUR + 1",
+            "1 EUR * 1 USD",
+            "1 EUR / 0",
+            "1 / 1 EUR",
+            "1 EUR / 1 USD",
+            "1 JPY in EUR",
+        ] {
+            assert!(matches!(total(s), Err(Error::InvalidCalculation(_))), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_breakdown() {
+        let evaluation = "125 USD + 2 * 40 GBP in EUR"
+            .parse::<Calculation>()
+            .unwrap()
+            .evaluate(&response())
+            .unwrap();
+
+        assert_eq!(evaluation.total, 200.0);
+        assert_eq!(
+            evaluation.terms,
+            [
+                Term {
+                    amount: 125.0,
+                    currency: Currency::USD,
+                    rate: 1.25,
+                    converted: 100.0,
+                },
+                Term {
+                    amount: 40.0,
+                    currency: Currency::GBP,
+                    rate: 0.8,
+                    converted: 50.0,
+                },
+            ]
+        );
+    }
+}
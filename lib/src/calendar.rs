@@ -0,0 +1,199 @@
+//! Helpers for the publication schedule of the European Central Bank's reference rates, which the
+//! Frankfurter API is based on.
+//!
+//! Rates are published on every business day, around 16:00 CET, except on TARGET closing days:
+//! New Year's Day, Good Friday, Easter Monday, Labour Day (1 May), Christmas Day and 26 December.
+//!
+//! # Example
+//! ```
+//! # use chrono::{NaiveDate, TimeZone, Utc};
+//! # use lib_frankfurter::calendar;
+//! // Saturday 6 January 2024
+//! let now = Utc.with_ymd_and_hms(2024, 1, 6, 12, 0, 0).unwrap();
+//! assert_eq!(
+//!     calendar::expected_latest_date(now),
+//!     NaiveDate::from_ymd_opt(2024, 1, 5).unwrap()
+//! );
+//! ```
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveTime, Utc, Weekday};
+
+/// Time of day (UTC) by which new rates are expected to be available.
+///
+/// This is later than the ECB's publication time of around 16:00 CET (15:00 UTC), to account for
+/// daylight saving time and for the API importing the new rates.
+pub const PUBLICATION_TIME_UTC: NaiveTime = NaiveTime::from_hms_opt(16, 0, 0).unwrap();
+
+/// Returns true if reference rates are published on the given date.
+pub fn is_business_day(date: NaiveDate) -> bool {
+    if matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+        return false;
+    }
+
+    let easter = easter_sunday(date.year());
+    let is_easter_holiday = easter.and_then(|e| e.checked_sub_days(Days::new(2))) == Some(date)
+        || easter.and_then(|e| e.checked_add_days(Days::new(1))) == Some(date);
+
+    !is_easter_holiday && !matches!((date.month(), date.day()), (1, 1) | (5, 1) | (12, 25 | 26))
+}
+
+/// Returns the most recent business day on or before the given date.
+pub fn last_business_day(mut date: NaiveDate) -> NaiveDate {
+    while !is_business_day(date) {
+        date = date.pred_opt().expect("Date out of range");
+    }
+    date
+}
+
+/// Returns the date of the most recent rates which are expected to be available at the given
+/// time, see [`PUBLICATION_TIME_UTC`].
+pub fn expected_latest_date(now: DateTime<Utc>) -> NaiveDate {
+    let today = now.date_naive();
+    if now.time() >= PUBLICATION_TIME_UTC {
+        last_business_day(today)
+    } else {
+        last_business_day(today.pred_opt().expect("Date out of range"))
+    }
+}
+
+/// Returns the next time after the given time at which new rates are expected to be available,
+/// see [`PUBLICATION_TIME_UTC`].
+pub fn next_publication(now: DateTime<Utc>) -> DateTime<Utc> {
+    let mut date = now.date_naive();
+    if now.time() >= PUBLICATION_TIME_UTC {
+        date = date.succ_opt().expect("Date out of range");
+    }
+    while !is_business_day(date) {
+        date = date.succ_opt().expect("Date out of range");
+    }
+
+    date.and_time(PUBLICATION_TIME_UTC).and_utc()
+}
+
+/// Calculate the date of Easter Sunday for the given year in the Gregorian calendar, using the
+/// anonymous Gregorian algorithm.
+fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_easter_sunday() {
+        for (y, m, d) in [
+            (2000, 4, 23),
+            (2019, 4, 21),
+            (2024, 3, 31),
+            (2025, 4, 20),
+            (2026, 4, 5),
+            (2038, 4, 25),
+        ] {
+            assert_eq!(easter_sunday(y), Some(date(y, m, d)));
+        }
+    }
+
+    #[test]
+    fn test_is_business_day() {
+        // WEEKDAYS
+        for d in 8..=12 {
+            assert!(is_business_day(date(2024, 1, d)));
+        }
+
+        // WEEKENDS
+        assert!(!is_business_day(date(2024, 1, 6)));
+        assert!(!is_business_day(date(2024, 1, 7)));
+
+        // TARGET CLOSING DAYS
+        for (y, m, d) in [
+            (2024, 1, 1),
+            (2024, 3, 29),
+            (2024, 4, 1),
+            (2024, 5, 1),
+            (2024, 12, 25),
+            (2024, 12, 26),
+            (2025, 4, 18),
+            (2025, 4, 21),
+        ] {
+            assert!(!is_business_day(date(y, m, d)));
+        }
+
+        // Not holidays
+        assert!(is_business_day(date(2024, 12, 24)));
+        assert!(is_business_day(date(2024, 12, 31)));
+        assert!(is_business_day(date(2024, 3, 28)));
+    }
+
+    #[test]
+    fn test_last_business_day() {
+        assert_eq!(last_business_day(date(2024, 1, 10)), date(2024, 1, 10));
+        assert_eq!(last_business_day(date(2024, 1, 7)), date(2024, 1, 5));
+        // Easter Monday back to the Thursday before Good Friday
+        assert_eq!(last_business_day(date(2024, 4, 1)), date(2024, 3, 28));
+        // Christmas
+        assert_eq!(last_business_day(date(2024, 12, 26)), date(2024, 12, 24));
+    }
+
+    #[test]
+    fn test_expected_latest_date() {
+        // Before publication on a business day
+        let now = Utc.with_ymd_and_hms(2024, 1, 10, 9, 0, 0).unwrap();
+        assert_eq!(expected_latest_date(now), date(2024, 1, 9));
+
+        // After publication on a business day
+        let now = Utc.with_ymd_and_hms(2024, 1, 10, 17, 0, 0).unwrap();
+        assert_eq!(expected_latest_date(now), date(2024, 1, 10));
+
+        // Monday morning
+        let now = Utc.with_ymd_and_hms(2024, 1, 8, 9, 0, 0).unwrap();
+        assert_eq!(expected_latest_date(now), date(2024, 1, 5));
+
+        // Holiday
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap();
+        assert_eq!(expected_latest_date(now), date(2023, 12, 29));
+    }
+
+    #[test]
+    fn test_next_publication() {
+        let publication = |y, m, d| date(y, m, d).and_time(PUBLICATION_TIME_UTC).and_utc();
+
+        // Before publication on a business day
+        let now = Utc.with_ymd_and_hms(2024, 1, 10, 9, 0, 0).unwrap();
+        assert_eq!(next_publication(now), publication(2024, 1, 10));
+
+        // After publication on a business day
+        let now = Utc.with_ymd_and_hms(2024, 1, 10, 16, 0, 0).unwrap();
+        assert_eq!(next_publication(now), publication(2024, 1, 11));
+
+        // Friday evening
+        let now = Utc.with_ymd_and_hms(2024, 1, 5, 20, 0, 0).unwrap();
+        assert_eq!(next_publication(now), publication(2024, 1, 8));
+
+        // Over Easter
+        let now = Utc.with_ymd_and_hms(2024, 3, 28, 18, 0, 0).unwrap();
+        assert_eq!(next_publication(now), publication(2024, 4, 2));
+    }
+}
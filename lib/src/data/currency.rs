@@ -89,8 +89,9 @@ pub enum Currency {
 /// Wrapper around an [`f64`], representing a valid currency value/amount.
 ///
 /// This wrapper ensures that:
-/// 1. Values are rounded to 2 decimal places when being displayed
-/// 2. Values are limited by min and max values defined by [`CurrencyValue::MIN`] and [`CurrencyValue::MAX`], using comparisons provided by [`fast_float_compare`]
+/// 1. Values are rounded to their precision, 2 decimal places by default, when being displayed or
+///    sent to the API
+/// 2. Values are limited by min and max values defined by [`CurrencyValue::min_with_precision`] and [`CurrencyValue::MAX`], using comparisons provided by [`fast_float_compare`]
 ///
 /// # Example
 /// ```
@@ -105,25 +106,93 @@ pub enum Currency {
 /// assert!(CurrencyValue::try_from(0.0).is_err());
 /// assert!(CurrencyValue::try_from(-0.2).is_err());
 /// assert!(CurrencyValue::try_from(f64::MAX).is_err());
+///
+/// // Sub-cent values require a higher precision
+/// assert!(CurrencyValue::try_from(0.005).is_err());
+/// assert!(CurrencyValue::new(0.005, 3).is_ok_and(|f| f.to_string() == "0.005"));
+/// assert_eq!(CurrencyValue::new(1234.5678, 4).unwrap().to_string(), "1234.5678");
 /// ```
-#[derive(Clone, Copy, PartialEq, PartialOrd, Debug, Deserialize, Serialize)]
-pub struct CurrencyValue(f64);
+#[derive(Clone, Copy, Debug)]
+pub struct CurrencyValue {
+    value: f64,
+    /// Number of decimal places to round the value to.
+    precision: u8,
+}
 
 impl Display for CurrencyValue {
-    // Limit to 2 decimal places
+    // Limit to the precision
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:.2}", self.0)
+        write!(f, "{:.*}", usize::from(self.precision), self.value)
     }
 }
 
 impl CurrencyValue {
-    pub const MIN: Self = CurrencyValue(0.01);
-    pub const MAX: Self = CurrencyValue(1_000_000_000_000.0);
+    /// Number of decimal places used unless another precision is given.
+    pub const DEFAULT_PRECISION: u8 = 2;
+    /// Maximum number of decimal places, e.g. for amounts of Bitcoin in satoshis.
+    pub const MAX_PRECISION: u8 = 8;
+
+    /// Minimum value at the [`CurrencyValue::DEFAULT_PRECISION`], see
+    /// [`CurrencyValue::min_with_precision`] for other precisions.
+    pub const MIN: Self = CurrencyValue {
+        value: 0.01,
+        precision: Self::DEFAULT_PRECISION,
+    };
+    pub const MAX: Self = CurrencyValue {
+        value: 1_000_000_000_000.0,
+        precision: Self::DEFAULT_PRECISION,
+    };
+
+    /// Create a [`CurrencyValue`] with the given number of decimal places, which must be at most
+    /// [`CurrencyValue::MAX_PRECISION`].
+    ///
+    /// The minimum value is the smallest non-zero value at that precision, see
+    /// [`CurrencyValue::min_with_precision`].
+    pub fn new(value: f64, precision: u8) -> Result<Self, Error> {
+        if precision <= Self::MAX_PRECISION && Self::is_valid_currency_value(value, precision) {
+            Ok(CurrencyValue { value, precision })
+        } else {
+            Err(Error::InvalidCurrencyValue(value.to_string()))
+        }
+    }
+
+    /// Parse a [`CurrencyValue`] with the given number of decimal places, see
+    /// [`CurrencyValue::new`].
+    pub fn parse(s: &str, precision: u8) -> Result<Self, Error> {
+        CurrencyValue::new(
+            f64::from_str(
+                // Ignore potential thousand separators
+                &s.replace([',', '_'], ""),
+            )
+            .map_err(|_| Error::InvalidCurrencyValue(s.to_owned()))?,
+            precision,
+        )
+    }
+
+    /// Consumes an existing [`CurrencyValue`] and returns one with the given number of decimal
+    /// places, if the value is still valid at that precision.
+    pub fn with_precision(self, precision: u8) -> Result<Self, Error> {
+        CurrencyValue::new(self.value, precision)
+    }
+
+    /// Number of decimal places the value is rounded to.
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Smallest non-zero value at the given number of decimal places, e.g. 0.001 for 3.
+    pub fn min_with_precision(precision: u8) -> Self {
+        let precision = precision.min(Self::MAX_PRECISION);
+        CurrencyValue {
+            value: 10_f64.powi(-i32::from(precision)),
+            precision,
+        }
+    }
 
-    fn is_valid_currency_value(value: f64) -> bool {
+    fn is_valid_currency_value(value: f64, precision: u8) -> bool {
         value.is_normal()
             && matches!(
-                (Float::from_f64(*CurrencyValue::MIN), Float::from_f64(value)),
+                (Float::from_f64(*CurrencyValue::min_with_precision(precision)), Float::from_f64(value)),
                 (Some(min), Some(value)) if value >= min
             )
             && matches!(
@@ -136,31 +205,50 @@ impl CurrencyValue {
 impl TryFrom<f64> for CurrencyValue {
     type Error = Error;
     fn try_from(value: f64) -> std::result::Result<Self, Self::Error> {
-        if Self::is_valid_currency_value(value) {
-            Ok(CurrencyValue(value))
-        } else {
-            Err(Error::InvalidCurrencyValue(value.to_string()))
-        }
+        CurrencyValue::new(value, Self::DEFAULT_PRECISION)
     }
 }
 
 impl FromStr for CurrencyValue {
     type Err = Error;
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        CurrencyValue::try_from(
-            f64::from_str(
-                // Ignore potential thousand separators
-                &s.replace([',', '_'], ""),
-            )
-            .map_err(|_| Error::InvalidCurrencyValue(s.to_owned()))?,
-        )
+        CurrencyValue::parse(s, Self::DEFAULT_PRECISION)
     }
 }
 
 impl Deref for CurrencyValue {
     type Target = f64;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.value
+    }
+}
+
+// Compared by value only, the precision just affects how the value is displayed
+impl PartialEq for CurrencyValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl PartialOrd for CurrencyValue {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+// (De)serialized as a plain number, so values returned by the API aren't limited by the precision
+impl Serialize for CurrencyValue {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CurrencyValue {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(CurrencyValue {
+            value: f64::deserialize(deserializer)?,
+            precision: Self::DEFAULT_PRECISION,
+        })
     }
 }
 
@@ -173,20 +261,20 @@ mod tests {
     #[test]
     fn test_currency_value_validity_checked_on_creation() {
         assert_eq!(
-            CurrencyValue::is_valid_currency_value(1.0),
+            CurrencyValue::is_valid_currency_value(1.0, 2),
             CurrencyValue::try_from(1.0).is_ok()
         );
         assert_eq!(
-            CurrencyValue::is_valid_currency_value(0.0),
+            CurrencyValue::is_valid_currency_value(0.0, 2),
             CurrencyValue::try_from(0.0).is_ok()
         );
 
         assert_eq!(
-            CurrencyValue::is_valid_currency_value(1.0),
+            CurrencyValue::is_valid_currency_value(1.0, 2),
             CurrencyValue::from_str("1.0").is_ok()
         );
         assert_eq!(
-            CurrencyValue::is_valid_currency_value(0.0),
+            CurrencyValue::is_valid_currency_value(0.0, 2),
             CurrencyValue::from_str("0.0").is_ok()
         );
         assert!(CurrencyValue::from_str("abc").is_err());
@@ -194,14 +282,22 @@ mod tests {
 
     #[test]
     fn test_is_valid_currency_value() {
-        assert!(CurrencyValue::is_valid_currency_value(*CurrencyValue::MIN));
-        assert!(CurrencyValue::is_valid_currency_value(*CurrencyValue::MAX));
+        assert!(CurrencyValue::is_valid_currency_value(
+            *CurrencyValue::MIN,
+            2
+        ));
+        assert!(CurrencyValue::is_valid_currency_value(
+            *CurrencyValue::MAX,
+            2
+        ));
 
         assert!(!CurrencyValue::is_valid_currency_value(
-            *CurrencyValue::MIN - 0.0001
+            *CurrencyValue::MIN - 0.0001,
+            2
         ));
         assert!(!CurrencyValue::is_valid_currency_value(
-            *CurrencyValue::MAX + 0.0001
+            *CurrencyValue::MAX + 0.0001,
+            2
         ));
 
         for val in [
@@ -210,21 +306,79 @@ mod tests {
             f64::NEG_INFINITY,
             f64::MIN_POSITIVE,
         ] {
-            assert!(!CurrencyValue::is_valid_currency_value(val));
+            assert!(!CurrencyValue::is_valid_currency_value(val, 2));
+        }
+    }
+
+    #[test]
+    fn test_precision() {
+        // MINIMUM
+        assert_eq!(CurrencyValue::min_with_precision(2), CurrencyValue::MIN);
+        assert_eq!(*CurrencyValue::min_with_precision(0), 1.0);
+        assert_eq!(*CurrencyValue::min_with_precision(8), 0.000_000_01);
+        assert_eq!(
+            CurrencyValue::min_with_precision(20),
+            CurrencyValue::min_with_precision(CurrencyValue::MAX_PRECISION)
+        );
+
+        assert!(CurrencyValue::new(0.009, 2).is_err());
+        assert!(CurrencyValue::new(0.009, 3).is_ok());
+        assert!(CurrencyValue::new(0.5, 0).is_err());
+        assert!(CurrencyValue::new(0.000_000_01, 8).is_ok());
+        assert!(CurrencyValue::new(1.0, CurrencyValue::MAX_PRECISION + 1).is_err());
+
+        // CHANGING PRECISION
+        let value = CurrencyValue::from_str("0.05").unwrap();
+        assert_eq!(value.precision(), CurrencyValue::DEFAULT_PRECISION);
+        assert_eq!(value.with_precision(4).unwrap().to_string(), "0.0500");
+        assert!(value.with_precision(1).is_err());
+
+        // COMPARISON
+        assert_eq!(value, value.with_precision(4).unwrap());
+        assert!(value < CurrencyValue::new(0.051, 3).unwrap());
+        assert!(value.with_precision(8).unwrap() > CurrencyValue::MIN);
+
+        // DISPLAY
+        for (value, precision, expected) in [
+            (1234.5678, 0, "1235"),
+            (1234.5678, 2, "1234.57"),
+            (1234.5678, 4, "1234.5678"),
+            (0.005, 3, "0.005"),
+            (0.000_000_15, 8, "0.00000015"),
+        ] {
+            assert_eq!(
+                CurrencyValue::new(value, precision).unwrap().to_string(),
+                expected
+            );
         }
+        assert_eq!(
+            CurrencyValue::parse("1,234.5678", 3).unwrap().to_string(),
+            "1234.568"
+        );
+    }
+
+    #[test]
+    fn test_serde() {
+        let value = CurrencyValue::new(0.005, 3).unwrap();
+        assert_eq!(serde_json::to_string(&value).unwrap(), "0.005");
+
+        // Values from the API aren't validated or limited by the precision
+        let value: CurrencyValue = serde_json::from_str("0.0001234").unwrap();
+        assert_eq!(*value, 0.0001234);
+        assert_eq!(value.precision(), CurrencyValue::DEFAULT_PRECISION);
     }
 
     proptest! {
         #[test]
         fn test_is_valid_currency_value_props(value in *CurrencyValue::MIN..=*CurrencyValue::MAX) {
-            assert!(CurrencyValue::is_valid_currency_value(value));
+            assert!(CurrencyValue::is_valid_currency_value(value, 2));
         }
     }
 
     #[test]
     fn test_currency_value_ignores_separators() {
-        let thousand = CurrencyValue(1_000.0);
-        let million = CurrencyValue(1_000_000.0);
+        let thousand = CurrencyValue::try_from(1_000.0).unwrap();
+        let million = CurrencyValue::try_from(1_000_000.0).unwrap();
 
         assert_eq!(thousand, CurrencyValue::from_str("1,000").unwrap());
         assert_eq!(thousand, CurrencyValue::from_str("1_000").unwrap());
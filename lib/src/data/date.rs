@@ -1,6 +1,6 @@
 use std::{fmt::Display, ops::Deref, str::FromStr};
 
-use chrono::{Local, NaiveDate};
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 use crate::Error;
@@ -34,7 +34,7 @@ impl ValidDate {
         Self::MIN
     }
     pub fn max() -> Self {
-        ValidDate(Local::now().date_naive())
+        ValidDate(crate::time::today())
     }
 
     fn is_valid_date(value: NaiveDate) -> bool {
@@ -1,5 +1,7 @@
 mod currency;
 mod date;
+mod quotation;
 
 pub use currency::*;
 pub use date::*;
+pub use quotation::*;
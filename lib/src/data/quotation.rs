@@ -0,0 +1,117 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use super::Currency;
+
+/// Convention for quoting an exchange rate between a base and a target currency.
+///
+/// The API quotes rates directly, as the amount of the target currency for the requested amount of
+/// the base currency. See `convert::Response::quote` and `period::Response::quote` for converting
+/// the rates of a response.
+///
+/// # Example
+/// ```
+/// # use lib_frankfurter::Quotation;
+/// // 2 EUR = 2.5 USD
+/// let (amount, value) = (2.0, 2.5);
+///
+/// assert_eq!(Quotation::default().rate(amount, value), 1.25);
+/// // 100 EUR = 125 USD
+/// assert_eq!(Quotation::Direct { per: 100 }.rate(amount, value), 125.0);
+/// // 1 USD = 0.8 EUR
+/// assert_eq!(Quotation::Indirect { per: 1 }.rate(amount, value), 0.8);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Serialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum Quotation {
+    /// Amount of the target currency for `per` units of the base currency.
+    Direct { per: u32 },
+    /// Amount of the base currency for `per` units of the target currency, e.g. EUR per USD.
+    Indirect { per: u32 },
+}
+
+impl Default for Quotation {
+    fn default() -> Self {
+        Self::Direct { per: 1 }
+    }
+}
+
+impl Quotation {
+    /// Number of units of the currency being quoted.
+    pub fn per(&self) -> u32 {
+        match self {
+            Self::Direct { per } | Self::Indirect { per } => *per,
+        }
+    }
+
+    /// Quote the given value of the target currency, for the given amount of the base currency.
+    pub fn rate(&self, amount: f64, value: f64) -> f64 {
+        let unit_rate = value / amount;
+        match self {
+            Self::Direct { per } => unit_rate * f64::from(*per),
+            Self::Indirect { per } => f64::from(*per) / unit_rate,
+        }
+    }
+
+    /// Describe the quoted rate for the given currencies, e.g. `EUR per 100 JPY`.
+    pub fn describe(&self, base: &Currency, target: &Currency) -> String {
+        match self {
+            Self::Direct { per } => format!("{target} per {per} {base}"),
+            Self::Indirect { per } => format!("{base} per {per} {target}"),
+        }
+    }
+}
+
+impl Display for Quotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Direct { per } => write!(f, "direct (per {per})"),
+            Self::Indirect { per } => write!(f, "indirect (per {per})"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_quotation {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_rate() {
+        // 10 EUR = 1750 JPY
+        let (amount, value) = (10.0, 1750.0);
+
+        assert_eq!(Quotation::default().rate(amount, value), 175.0);
+        assert_eq!(Quotation::Direct { per: 100 }.rate(amount, value), 17500.0);
+        assert_eq!(
+            Quotation::Indirect { per: 1 }.rate(amount, value),
+            1.0 / 175.0
+        );
+        assert_eq!(
+            Quotation::Indirect { per: 100 }.rate(amount, value),
+            100.0 / 175.0
+        );
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(
+            Quotation::default().describe(&Currency::EUR, &Currency::USD),
+            "USD per 1 EUR"
+        );
+        assert_eq!(
+            Quotation::Indirect { per: 100 }.describe(&Currency::EUR, &Currency::JPY),
+            "EUR per 100 JPY"
+        );
+    }
+
+    #[test]
+    fn test_serde() {
+        assert_eq!(
+            serde_json::to_string(&Quotation::Indirect { per: 100 }).unwrap(),
+            r#"{"mode":"indirect","per":100}"#
+        );
+    }
+}
@@ -1,6 +1,11 @@
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
 use reqwest::StatusCode;
+use serde::Deserialize;
+use url::Url;
 
-use crate::{CurrencyValue, ValidDate, data::Currency};
+use crate::{CurrencyValue, ValidDate, api::DEFAULT_API_VERSION_PATH, data::Currency};
 
 /// [`std::result::Result`] wrapper for convenience.
 pub(super) type Result<T> = std::result::Result<T, Error>;
@@ -18,9 +23,11 @@ pub enum Error {
     RequestEndDateBeforeStart { start: ValidDate, end: ValidDate },
 
     #[error(
-        "Invalid currency value ({0}), must be a valid number between {min} and {max}",
+        "Invalid currency value ({0}), must be a valid number between {min} and {max}, or down to {min_precise} with a precision of up to {max_precision} decimal places",
         min = *CurrencyValue::MIN,
-        max = *CurrencyValue::MAX
+        max = *CurrencyValue::MAX,
+        min_precise = CurrencyValue::min_with_precision(CurrencyValue::MAX_PRECISION),
+        max_precision = CurrencyValue::MAX_PRECISION,
     )]
     InvalidCurrencyValue(String),
 
@@ -31,10 +38,45 @@ pub enum Error {
     )]
     InvalidDate(String),
 
-    #[error("Invalid response from the API\n  URL - {url}\n  Status - {status}\n  Body - {body}")]
+    #[error(
+        "Invalid alert rule ({0}), currency pairs must be of the form BASE/TARGET, e.g. EUR/USD, with a positive threshold"
+    )]
+    InvalidAlertRule(String),
+
+    #[error("Invalid calculation ({0})")]
+    InvalidCalculation(String),
+
+    #[error(
+        "Invalid commodity symbol ({0}), which can't be empty or contain quotes, and for beancount must be capital letters, digits or '._- starting with a letter"
+    )]
+    InvalidCommodity(String),
+
+    #[error("Cannot compare exchange rates with different base currencies ({from} and {to})")]
+    DiffBaseMismatch { from: Currency, to: Currency },
+
+    /// No fixture was recorded for a request made in [`FixtureMode::Replay`].
+    ///
+    /// [`FixtureMode::Replay`]: crate::api::fixtures::FixtureMode::Replay
+    #[error("No fixture recorded for the request ({request}), expected at {}", .path.display())]
+    MissingFixture { request: String, path: PathBuf },
+
+    #[error("Invalid fixture at {}", .0.display())]
+    InvalidFixture(PathBuf),
+
+    /// An error status code was returned from the API.
+    ///
+    /// The `kind` is parsed from the status code and the `message` of the JSON body, if any, so
+    /// that callers don't need to match on the raw `body`.
+    #[error(
+        "Invalid response from the API ({kind})\n  URL - {url}\n  Status - {status}\n  Body - {body}"
+    )]
     InvalidResponse {
+        kind: ResponseErrorKind,
+        /// Full URL of the original request, including the query parameters.
         url: String,
         status: StatusCode,
+        /// Message parsed from a JSON body of the form `{"message": "..."}`.
+        message: Option<String>,
         body: String,
     },
 
@@ -44,7 +86,460 @@ pub enum Error {
     /// Error from [`serde_json`], see [`serde_json::Error`].
     #[error(transparent)]
     SerdeJSON(#[from] serde_json::Error),
-    /// Error from [`std::io`], see [`std::io::Error`].
+    /// Error from [`std::io`], see [`std::io::Error`], which also covers errors writing CSV with the
+    /// `csv` feature.
     #[error(transparent)]
     IO(#[from] std::io::Error),
 }
+
+/// Errors writing CSV are converted to [`Error::IO`], rather than having their own variant, so
+/// that enabling the `csv` feature doesn't change the variants of [`Error`].
+#[cfg(feature = "csv")]
+impl From<csv::Error> for Error {
+    fn from(e: csv::Error) -> Self {
+        Self::IO(e.into())
+    }
+}
+
+impl Error {
+    /// Construct an [`Error::InvalidResponse`], parsing the body returned by the API.
+    pub(crate) fn invalid_response(url: &Url, status: StatusCode, body: String) -> Self {
+        let message = serde_json::from_str::<ErrorBody>(&body)
+            .ok()
+            .map(|b| b.message);
+
+        Self::InvalidResponse {
+            kind: ResponseErrorKind::classify(status, message.as_deref(), url),
+            url: url.to_string(),
+            status,
+            message,
+            body,
+        }
+    }
+
+    /// Returns true if the same request could succeed when retried later, e.g. after a timeout,
+    /// being rate limited or a server error.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::InvalidResponse { kind, .. } => matches!(
+                kind,
+                ResponseErrorKind::RateLimited | ResponseErrorKind::ServerError
+            ),
+            Self::Reqwest(e) => {
+                e.is_timeout()
+                    || is_connect(e)
+                    || e.is_body()
+                    || e.status()
+                        .is_some_and(|s| s == StatusCode::TOO_MANY_REQUESTS || s.is_server_error())
+            }
+            Self::IO(e) => is_network_io_error(e),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the request was rejected before being sent, due to invalid input.
+    pub fn is_client_validation(&self) -> bool {
+        matches!(
+            self,
+            Self::RequestTargetsIncludeBase { .. }
+                | Self::RequestEndDateBeforeStart { .. }
+                | Self::InvalidCurrencyValue(_)
+                | Self::InvalidDate(_)
+                | Self::InvalidAlertRule(_)
+                | Self::InvalidCalculation(_)
+                | Self::InvalidCommodity(_)
+                | Self::DiffBaseMismatch { .. }
+        )
+    }
+
+    /// Returns true if the API could not be reached, or the connection failed before a full
+    /// response was received.
+    pub fn is_network(&self) -> bool {
+        match self {
+            Self::Reqwest(e) => is_connect(e) || e.is_timeout() || e.is_request() || e.is_body(),
+            Self::IO(e) => is_network_io_error(e),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the API responded with a `404 Not Found` status code.
+    ///
+    /// Note that this includes unknown currencies, see [`ResponseErrorKind::UnknownCurrency`].
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(StatusCode::NOT_FOUND)
+    }
+
+    /// The status code of the response from the API, if one was received.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::InvalidResponse { status, .. } => Some(*status),
+            Self::Reqwest(e) => e.status(),
+            _ => None,
+        }
+    }
+}
+
+/// Returns true if the given [`reqwest::Error`] came from connecting to the API.
+///
+/// When compiled for WebAssembly, `fetch` doesn't distinguish connection errors, so any error
+/// sending the request counts.
+fn is_connect(e: &reqwest::Error) -> bool {
+    #[cfg(not(target_arch = "wasm32"))]
+    return e.is_connect();
+    #[cfg(target_arch = "wasm32")]
+    return e.is_request();
+}
+
+/// Returns true if the given [`std::io::Error`] was caused by a network failure.
+fn is_network_io_error(e: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+
+    matches!(
+        e.kind(),
+        ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::TimedOut
+            | ErrorKind::UnexpectedEof
+    )
+}
+
+/// JSON body returned by the API alongside an error status code.
+#[derive(Deserialize)]
+struct ErrorBody {
+    message: String,
+}
+
+/// The kind of error response returned by the API, see [`Error::InvalidResponse`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+#[non_exhaustive]
+pub enum ResponseErrorKind {
+    /// The base or one of the target currencies is not known to the API.
+    #[strum(serialize = "unknown currency")]
+    UnknownCurrency,
+    /// The requested date or time period could not be handled by the API.
+    #[strum(serialize = "invalid date")]
+    InvalidDate,
+    /// The requested resource doesn't exist, e.g. the API URL is incorrect.
+    #[strum(serialize = "not found")]
+    NotFound,
+    /// Too many requests have been made to the API.
+    #[strum(serialize = "rate limited")]
+    RateLimited,
+    /// The API failed to handle an otherwise valid request.
+    #[strum(serialize = "server error")]
+    ServerError,
+    /// Any other error response.
+    Other,
+}
+
+/// Words in the error messages of the API about currencies, e.g. "invalid base currency".
+const CURRENCY_WORDS: [&str; 5] = ["currency", "currencies", "base", "symbol", "symbols"];
+/// Words in the error messages of the API about dates, e.g. "invalid date".
+const DATE_WORDS: [&str; 2] = ["date", "dates"];
+
+impl ResponseErrorKind {
+    /// Determine the kind of an error response from its status code, the message from its body and
+    /// the URL of the request which caused it.
+    fn classify(status: StatusCode, message: Option<&str>, url: &Url) -> Self {
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return Self::RateLimited;
+        }
+        if status.is_server_error() {
+            return Self::ServerError;
+        }
+
+        let message = message.map(str::to_lowercase);
+        // Whole words only, e.g. "database" isn't about the base currency
+        let has_word = |words: &[&str]| {
+            message.as_deref().is_some_and(|m| {
+                m.split(|c: char| !c.is_ascii_alphanumeric())
+                    .any(|w| words.contains(&w))
+            })
+        };
+
+        if has_word(&CURRENCY_WORDS) {
+            Self::UnknownCurrency
+        } else if has_word(&DATE_WORDS) {
+            Self::InvalidDate
+        } else if status == StatusCode::NOT_FOUND
+            && message.is_some()
+            && is_endpoint(url)
+            && url
+                .query_pairs()
+                .any(|(k, _)| k == "base" || k == "symbols")
+        {
+            // The API responds to unknown currency codes with a generic "not found" message, so
+            // fall back to checking whether any currencies were requested from a known endpoint
+            Self::UnknownCurrency
+        } else if status == StatusCode::NOT_FOUND {
+            Self::NotFound
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// Returns true if the path of the URL ends with an endpoint of the API, e.g. `/v1/latest` or
+/// `/v1/2024-01-02..`, rather than a path which doesn't exist, e.g. of another API version.
+fn is_endpoint(url: &Url) -> bool {
+    let mut segments = url
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .filter(|s| !s.is_empty())
+        .rev();
+    let Some(endpoint) = segments.next() else {
+        return false;
+    };
+    let is_date = |s: &str| s.parse::<NaiveDate>().is_ok();
+    let (start, end) = endpoint.split_once("..").unwrap_or((endpoint, ""));
+    let is_known = matches!(endpoint, "latest" | "currencies")
+        || (is_date(start) && (end.is_empty() || is_date(end)));
+
+    // Mirrors may be mounted without a version, but any version must be the supported one
+    let is_version = |s: &str| {
+        s.strip_prefix('v')
+            .is_some_and(|v| v.parse::<u32>().is_ok())
+    };
+    is_known
+        && segments
+            .next()
+            .is_none_or(|s| !is_version(s) || s == DEFAULT_API_VERSION_PATH)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn kind_of(url: &str, status: StatusCode, body: &str) -> ResponseErrorKind {
+        match Error::invalid_response(&Url::parse(url).unwrap(), status, body.to_owned()) {
+            Error::InvalidResponse { kind, .. } => kind,
+            e => panic!("unexpected error: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_response_message() {
+        let url = Url::parse("http://localhost:8080/v1/latest?base=ABC").unwrap();
+
+        let Error::InvalidResponse { message, body, .. } = Error::invalid_response(
+            &url,
+            StatusCode::NOT_FOUND,
+            r#"{"message":"not found"}"#.to_owned(),
+        ) else {
+            unreachable!()
+        };
+        assert_eq!(message.as_deref(), Some("not found"));
+        assert_eq!(body, r#"{"message":"not found"}"#);
+
+        let Error::InvalidResponse { message, .. } =
+            Error::invalid_response(&url, StatusCode::NOT_FOUND, "<html></html>".to_owned())
+        else {
+            unreachable!()
+        };
+        assert_eq!(message, None);
+    }
+
+    #[test]
+    fn test_response_error_kind() {
+        const LATEST: &str = "http://localhost:8080/v1/latest";
+
+        // UNKNOWN CURRENCY
+        assert_eq!(
+            kind_of(
+                &format!("{LATEST}?base=ABC"),
+                StatusCode::NOT_FOUND,
+                r#"{"message":"not found"}"#
+            ),
+            ResponseErrorKind::UnknownCurrency
+        );
+        assert_eq!(
+            kind_of(
+                &format!("{LATEST}?symbols=USD,ABC"),
+                StatusCode::NOT_FOUND,
+                r#"{"message":"not found"}"#
+            ),
+            ResponseErrorKind::UnknownCurrency
+        );
+        assert_eq!(
+            kind_of(
+                LATEST,
+                StatusCode::UNPROCESSABLE_ENTITY,
+                r#"{"message":"invalid base currency"}"#
+            ),
+            ResponseErrorKind::UnknownCurrency
+        );
+        // Of periods, and of mirrors without an API version in the path
+        for url in [
+            "http://localhost:8080/v1/2024-01-02..?symbols=ABC",
+            "http://localhost:8080/v1/2024-01-02..2024-02-01?base=ABC",
+            "http://localhost:8080/mirror/latest?base=ABC",
+        ] {
+            assert_eq!(
+                kind_of(url, StatusCode::NOT_FOUND, r#"{"message":"not found"}"#),
+                ResponseErrorKind::UnknownCurrency,
+                "{url}"
+            );
+        }
+
+        // INVALID DATE
+        assert_eq!(
+            kind_of(
+                "http://localhost:8080/v1/2024-13-01",
+                StatusCode::UNPROCESSABLE_ENTITY,
+                r#"{"message":"invalid date"}"#
+            ),
+            ResponseErrorKind::InvalidDate
+        );
+
+        // NOT FOUND
+        assert_eq!(
+            kind_of(LATEST, StatusCode::NOT_FOUND, r#"{"message":"not found"}"#),
+            ResponseErrorKind::NotFound
+        );
+        assert_eq!(
+            kind_of(&format!("{LATEST}?base=USD"), StatusCode::NOT_FOUND, ""),
+            ResponseErrorKind::NotFound
+        );
+        // Wrong paths, e.g. of another API version, with currencies in the query
+        for url in [
+            "http://localhost:8080/v2/latest?base=USD",
+            "http://localhost:8080/v1/lates?base=USD",
+            "http://localhost:8080/v1/2024-13-01?symbols=USD",
+            "http://localhost:8080/?base=USD",
+        ] {
+            assert_eq!(
+                kind_of(url, StatusCode::NOT_FOUND, r#"{"message":"not found"}"#),
+                ResponseErrorKind::NotFound,
+                "{url}"
+            );
+        }
+        // Only whole words of messages, as "database" isn't about the base currency
+        assert_eq!(
+            kind_of(
+                LATEST,
+                StatusCode::NOT_FOUND,
+                r#"{"message":"database error"}"#
+            ),
+            ResponseErrorKind::NotFound
+        );
+
+        // RATE LIMITED
+        assert_eq!(
+            kind_of(
+                &format!("{LATEST}?base=USD"),
+                StatusCode::TOO_MANY_REQUESTS,
+                r#"{"message":"too many requests"}"#
+            ),
+            ResponseErrorKind::RateLimited
+        );
+
+        // SERVER ERROR
+        for status in [StatusCode::INTERNAL_SERVER_ERROR, StatusCode::BAD_GATEWAY] {
+            assert_eq!(
+                kind_of(
+                    &format!("{LATEST}?base=USD"),
+                    status,
+                    "Internal Server Error"
+                ),
+                ResponseErrorKind::ServerError
+            );
+        }
+
+        // OTHER
+        for message in ["database error", "update failed", "unknown symbolic link"] {
+            assert_eq!(
+                kind_of(
+                    LATEST,
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    &format!(r#"{{"message":"{message}"}}"#)
+                ),
+                ResponseErrorKind::Other,
+                "{message}"
+            );
+        }
+        assert_eq!(
+            kind_of(
+                LATEST,
+                StatusCode::BAD_REQUEST,
+                r#"{"message":"bad request"}"#
+            ),
+            ResponseErrorKind::Other
+        );
+    }
+
+    #[test]
+    fn test_error_classification() {
+        let url = Url::parse("http://localhost:8080/v1/latest?base=USD").unwrap();
+        let response = |status| Error::invalid_response(&url, status, String::new());
+
+        // CLIENT VALIDATION
+        for e in [
+            Error::RequestTargetsIncludeBase {
+                base: Currency::EUR,
+                targets: vec![Currency::EUR],
+            },
+            Error::RequestEndDateBeforeStart {
+                start: ValidDate::max(),
+                end: ValidDate::min(),
+            },
+            Error::InvalidCurrencyValue(String::from("0")),
+            Error::InvalidDate(String::from("date")),
+            Error::InvalidAlertRule(String::from("EUR")),
+            Error::DiffBaseMismatch {
+                from: Currency::EUR,
+                to: Currency::USD,
+            },
+        ] {
+            assert!(e.is_client_validation());
+            assert!(!e.is_retryable());
+            assert!(!e.is_network());
+            assert!(!e.is_not_found());
+            assert_eq!(e.status(), None);
+        }
+
+        // NOT FOUND
+        let e = response(StatusCode::NOT_FOUND);
+        assert!(e.is_not_found());
+        assert!(!e.is_retryable());
+        assert!(!e.is_client_validation());
+        assert_eq!(e.status(), Some(StatusCode::NOT_FOUND));
+
+        // RETRYABLE
+        for status in [
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::SERVICE_UNAVAILABLE,
+        ] {
+            let e = response(status);
+            assert!(e.is_retryable());
+            assert!(!e.is_network());
+            assert!(!e.is_not_found());
+            assert_eq!(e.status(), Some(status));
+        }
+        assert!(!response(StatusCode::BAD_REQUEST).is_retryable());
+
+        // NETWORK
+        let e = Error::from(std::io::Error::from(std::io::ErrorKind::ConnectionRefused));
+        assert!(e.is_network());
+        assert!(e.is_retryable());
+        assert_eq!(e.status(), None);
+
+        let e = Error::from(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+        assert!(!e.is_network());
+        assert!(!e.is_retryable());
+
+        // OTHER
+        let e = Error::from(serde_json::from_str::<serde_json::Value>("").unwrap_err());
+        assert!(!e.is_retryable());
+        assert!(!e.is_client_validation());
+        assert!(!e.is_network());
+        assert!(!e.is_not_found());
+        assert_eq!(e.status(), None);
+    }
+}
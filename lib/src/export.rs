@@ -0,0 +1,270 @@
+//! Export responses as CSV or TSV, with a header row, e.g. to load them into a spreadsheet or a
+//! database.
+//!
+//! Responses are written in long format, with one record per currency, and per date for time
+//! periods. Values are written at their full precision, rather than rounded for display.
+//!
+//! # Example
+//! ```
+//! # use lib_frankfurter::{api::period, export::{Export, Format}};
+//! let resp: period::Response = serde_json::from_str(
+//!     r#"{"base": "EUR", "amount": 1.0, "start_date": "2024-01-08", "end_date": "2024-01-09",
+//!        "rates": {"2024-01-08": {"USD": 1.0946}, "2024-01-09": {"USD": 1.0942}}}"#,
+//! )
+//! .unwrap();
+//!
+//! let mut out = Vec::new();
+//! resp.export(&mut out, Format::Csv).unwrap();
+//! assert_eq!(
+//!     String::from_utf8(out).unwrap(),
+//!     "date,base,currency,value\n2024-01-08,EUR,USD,1.0946\n2024-01-09,EUR,USD,1.0942\n"
+//! );
+//! ```
+
+use std::io::Write;
+
+use strum::{Display, EnumString, VariantNames};
+
+use crate::{
+    api::{
+        convert, currencies,
+        period::{self, Pivot},
+    },
+    error::Result,
+};
+
+/// Format of the exported records.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display, EnumString, VariantNames)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Format {
+    /// Comma-separated values, quoting fields where needed.
+    Csv,
+    /// Tab-separated values, quoting fields where needed.
+    Tsv,
+}
+
+impl Format {
+    fn delimiter(&self) -> u8 {
+        match self {
+            Self::Csv => b',',
+            Self::Tsv => b'\t',
+        }
+    }
+}
+
+/// Write the header and records to the writer in the given format, quoting and escaping fields
+/// where needed.
+pub fn write_records<W, H, R, F>(
+    writer: W,
+    format: Format,
+    header: impl IntoIterator<Item = H>,
+    records: impl IntoIterator<Item = R>,
+) -> Result<()>
+where
+    W: Write,
+    H: AsRef<[u8]>,
+    R: IntoIterator<Item = F>,
+    F: AsRef<[u8]>,
+{
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(format.delimiter())
+        .from_writer(writer);
+
+    writer.write_record(header)?;
+    for record in records {
+        writer.write_record(record)?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Responses which can be exported as CSV or TSV, see [`write_records`].
+pub trait Export {
+    /// Names of the fields of each record.
+    fn header(&self) -> Vec<String>;
+
+    /// Records to export, with the fields given by [`Export::header`].
+    fn records(&self) -> Vec<Vec<String>>;
+
+    /// Write the header and records to the writer in the given format.
+    fn export(&self, writer: impl Write, format: Format) -> Result<()> {
+        write_records(writer, format, self.header(), self.records())
+    }
+}
+
+/// Header of responses in long format.
+fn long_header() -> Vec<String> {
+    ["date", "base", "currency", "value"]
+        .map(String::from)
+        .to_vec()
+}
+
+impl Export for convert::Response {
+    fn header(&self) -> Vec<String> {
+        long_header()
+    }
+
+    fn records(&self) -> Vec<Vec<String>> {
+        self.rates
+            .iter()
+            .map(|(currency, value)| {
+                vec![
+                    self.date.to_string(),
+                    self.base.to_string(),
+                    currency.to_string(),
+                    (**value).to_string(),
+                ]
+            })
+            .collect()
+    }
+}
+
+impl Export for period::Response {
+    fn header(&self) -> Vec<String> {
+        long_header()
+    }
+
+    fn records(&self) -> Vec<Vec<String>> {
+        self.rates
+            .iter()
+            .flat_map(|(date, rates)| {
+                rates.iter().map(move |(currency, value)| {
+                    vec![
+                        date.to_string(),
+                        self.base.to_string(),
+                        currency.to_string(),
+                        (**value).to_string(),
+                    ]
+                })
+            })
+            .collect()
+    }
+}
+
+/// Wide format, with a record per date and a field per currency, which is empty for any date
+/// without a rate for the currency.
+impl Export for Pivot {
+    fn header(&self) -> Vec<String> {
+        std::iter::once(String::from("date"))
+            .chain(self.currencies.iter().map(ToString::to_string))
+            .collect()
+    }
+
+    fn records(&self) -> Vec<Vec<String>> {
+        self.rows
+            .iter()
+            .map(|(date, values)| {
+                std::iter::once(date.to_string())
+                    .chain(
+                        values
+                            .iter()
+                            .map(|v| v.map(|v| (*v).to_string()).unwrap_or_default()),
+                    )
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl Export for currencies::Response {
+    fn header(&self) -> Vec<String> {
+        vec![String::from("code"), String::from("name")]
+    }
+
+    fn records(&self) -> Vec<Vec<String>> {
+        self.0
+            .iter()
+            .map(|(code, name)| vec![code.to_string(), name.clone()])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests_export {
+    use std::str::FromStr;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn export(data: &impl Export, format: Format) -> String {
+        let mut out = Vec::new();
+        data.export(&mut out, format).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(Format::from_str("CSV").unwrap(), Format::Csv);
+        assert_eq!(Format::from_str("tsv").unwrap(), Format::Tsv);
+        assert!(Format::from_str("json").is_err());
+        assert_eq!(Format::VARIANTS, ["csv", "tsv"]);
+    }
+
+    #[test]
+    fn test_convert() {
+        let resp: convert::Response = serde_json::from_str(
+            r#"{"base": "EUR", "amount": 10.0, "date": "2024-01-09", "rates": {"GBP": 8.6123, "USD": 10.942}}"#,
+        )
+        .unwrap();
+
+        // Full precision, rather than rounded to the precision of the amount
+        assert_eq!(
+            export(&resp, Format::Csv),
+            "date,base,currency,value\n2024-01-09,EUR,GBP,8.6123\n2024-01-09,EUR,USD,10.942\n"
+        );
+        assert_eq!(
+            export(&resp, Format::Tsv),
+            "date\tbase\tcurrency\tvalue\n2024-01-09\tEUR\tGBP\t8.6123\n2024-01-09\tEUR\tUSD\t10.942\n"
+        );
+    }
+
+    #[test]
+    fn test_pivot() {
+        let resp: period::Response = serde_json::from_str(
+            r#"{"base": "EUR", "amount": 1.0, "start_date": "2024-01-08", "end_date": "2024-01-09",
+                "rates": {"2024-01-08": {"GBP": 0.86, "USD": 1.09}, "2024-01-09": {"USD": 1.1}}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            export(&resp.pivot(), Format::Csv),
+            "date,GBP,USD\n2024-01-08,0.86,1.09\n2024-01-09,,1.1\n"
+        );
+    }
+
+    #[test]
+    fn test_currencies_quoting() {
+        let resp: currencies::Response =
+            serde_json::from_str(r#"{"EUR": "Euro", "USD": "United States \"US\" Dollar, USD"}"#)
+                .unwrap();
+
+        assert_eq!(
+            export(&resp, Format::Csv),
+            "code,name\nEUR,Euro\nUSD,\"United States \"\"US\"\" Dollar, USD\"\n"
+        );
+        // Commas don't need quoting in TSV
+        assert_eq!(
+            export(&resp, Format::Tsv),
+            "code\tname\nEUR\tEuro\nUSD\t\"United States \"\"US\"\" Dollar, USD\"\n"
+        );
+    }
+
+    #[test]
+    fn test_write_records() {
+        let mut out = Vec::new();
+        write_records(
+            &mut out,
+            Format::Tsv,
+            ["a", "b"],
+            [["1", "two\tparts"], ["3", ""]],
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "a\tb\n1\t\"two\tparts\"\n3\t\n"
+        );
+    }
+}
@@ -7,9 +7,22 @@
 #![doc = include_str!("../examples/basic.rs")]
 //! ```
 
+#[cfg(all(feature = "watch", target_arch = "wasm32"))]
+compile_error!(
+    "The `watch` feature relies on `tokio` timers, which aren't available on WebAssembly"
+);
+
+pub mod alert;
 pub mod api;
+pub mod calc;
+pub mod calendar;
 mod data;
 mod error;
+#[cfg(feature = "csv")]
+pub mod export;
+pub mod prices;
+mod time;
+mod trace;
 
 // RE-EXPORTS
 pub use chrono;
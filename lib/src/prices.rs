@@ -0,0 +1,532 @@
+//! Price directives for plain-text accounting tools, i.e. [ledger](https://ledger-cli.org),
+//! [hledger](https://hledger.org) and [beancount](https://beancount.github.io), and prices as CSV
+//! for [GnuCash](https://gnucash.org)'s price importer and spreadsheets, from the responses of the
+//! [`convert`] and [`period`] endpoints.
+//!
+//! Each directive gives the price of one unit of a target currency in the base currency, e.g.
+//! `P 2024-01-02 USD 0.91 EUR` for hledger, `2024-01-02 price USD 0.91 EUR` for beancount, or
+//! `2024-01-02,USD,EUR,0.91` for GnuCash.
+//!
+//! # Example
+//! ```
+//! # use lib_frankfurter::{Currency, api::period, prices::{PriceFormat, PriceWriter, Prices}};
+//! let resp: period::Response = serde_json::from_str(
+//!     r#"{"base": "EUR", "amount": 1.0, "start_date": "2024-01-08", "end_date": "2024-01-09",
+//!        "rates": {"2024-01-08": {"USD": 1.25}, "2024-01-09": {"USD": 1.6}}}"#,
+//! )
+//! .unwrap();
+//!
+//! // Only the date missing from an existing price file is written
+//! let writer = PriceWriter::new(PriceFormat::Hledger)
+//!     .with_symbol(Currency::EUR, "€")
+//!     .with_existing("P 2024-01-08 USD 0.8 €\n".as_bytes())
+//!     .unwrap();
+//!
+//! let mut out = Vec::new();
+//! assert_eq!(writer.write(&mut out, resp.prices()).unwrap(), 1);
+//! assert_eq!(String::from_utf8(out).unwrap(), "P 2024-01-09 USD 0.625000 €\n");
+//! ```
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    io::{BufRead, Write},
+};
+
+use chrono::NaiveDate;
+use strum::{Display, EnumString, VariantNames};
+
+use crate::{
+    api::{convert, period},
+    data::{Currency, Quotation, ValidDate},
+    error::{Error, Result},
+};
+
+/// Default number of decimal places of prices.
+const DEFAULT_DECIMALS: usize = 6;
+
+/// Format of the price directives.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Display, EnumString, VariantNames)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum PriceFormat {
+    /// `P 2024/01/02 USD 0.91 EUR`
+    Ledger,
+    /// `P 2024-01-02 USD 0.91 EUR`
+    Hledger,
+    /// `2024-01-02 price USD 0.91 EUR`
+    Beancount,
+    /// `2024-01-02,USD,EUR,0.91`, after a `date,commodity,currency,price` header
+    Gnucash,
+    /// `"2024-01-02","USD","EUR",0.91`, after a quoted header, for importers which expect quoted
+    /// strings
+    #[strum(serialize = "gnucash-quoted")]
+    GnucashQuoted,
+}
+
+/// Price of one unit of a commodity in a currency, on a date.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Price {
+    pub date: ValidDate,
+    pub commodity: Currency,
+    pub price: f64,
+    pub currency: Currency,
+}
+
+/// Responses which can be written as price directives, see [`PriceWriter`].
+pub trait Prices {
+    /// Get the price of one unit of each target currency in the base currency, by date.
+    fn prices(&self) -> Vec<Price>;
+}
+
+impl Prices for convert::Response {
+    fn prices(&self) -> Vec<Price> {
+        self.quote(Quotation::Indirect { per: 1 })
+            .into_iter()
+            .map(|(commodity, price)| Price {
+                date: self.date,
+                commodity,
+                price,
+                currency: self.base.clone(),
+            })
+            .collect()
+    }
+}
+
+impl Prices for period::Response {
+    fn prices(&self) -> Vec<Price> {
+        self.quote(Quotation::Indirect { per: 1 })
+            .into_iter()
+            .flat_map(|(date, prices)| {
+                prices.into_iter().map(move |(commodity, price)| Price {
+                    date,
+                    commodity,
+                    price,
+                    currency: self.base.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Writer of price directives, with the symbols to use for currencies and the prices which are
+/// already in a price file, to only append the missing ones.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PriceWriter {
+    format: PriceFormat,
+    symbols: BTreeMap<Currency, String>,
+    decimals: usize,
+    /// Dates, commodity symbols and currency symbols of the existing prices.
+    existing: BTreeSet<(NaiveDate, String, String)>,
+    /// Whether to write the header of CSV formats, which isn't repeated in an existing price file.
+    header: bool,
+}
+
+impl PriceWriter {
+    /// Create a writer of the given format, using currency codes as commodity symbols.
+    pub fn new(format: PriceFormat) -> Self {
+        Self {
+            format,
+            symbols: BTreeMap::new(),
+            decimals: DEFAULT_DECIMALS,
+            existing: BTreeSet::new(),
+            header: true,
+        }
+    }
+
+    /// Consumes the [`PriceWriter`] and returns a new one using the given symbol for a currency,
+    /// e.g. `$` for USD.
+    pub fn with_symbol(mut self, currency: Currency, symbol: impl Into<String>) -> Self {
+        self.symbols.insert(currency, symbol.into());
+        self
+    }
+
+    /// Consumes the [`PriceWriter`] and returns a new one writing prices with the given number of
+    /// decimal places.
+    pub fn with_decimals(mut self, decimals: usize) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// Consumes the [`PriceWriter`] and returns a new one skipping the prices in an existing price
+    /// file, by their date, commodity and currency, so prices in other currencies are still written.
+    ///
+    /// Lines other than price directives are ignored, so this can read a full journal.
+    pub fn with_existing(mut self, reader: impl BufRead) -> Result<Self> {
+        for line in reader.lines() {
+            let line = line?;
+            self.header &= line.trim().is_empty();
+            if let Some(existing) = parse_directive(&line) {
+                self.existing.insert(existing);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Get the symbol of a currency, which defaults to its code.
+    pub fn symbol<'a>(&'a self, currency: &Currency) -> Cow<'a, str> {
+        match self.symbols.get(currency) {
+            Some(symbol) => Cow::Borrowed(symbol),
+            None => Cow::Owned(currency.to_string()),
+        }
+    }
+
+    /// Get the symbol of a currency, quoted if the format requires it.
+    fn commodity(&self, currency: &Currency) -> Result<String> {
+        let symbol = self.symbol(currency);
+
+        match self.format {
+            // Commodities must start with a capital letter, followed by capitals, digits or `'._-`
+            PriceFormat::Beancount => {
+                let mut chars = symbol.chars();
+                let valid = chars.next().is_some_and(|c| c.is_ascii_uppercase())
+                    && chars.all(|c| {
+                        c.is_ascii_uppercase() || c.is_ascii_digit() || "'._-".contains(c)
+                    });
+                if valid {
+                    Ok(symbol.into_owned())
+                } else {
+                    Err(Error::InvalidCommodity(symbol.into_owned()))
+                }
+            }
+            // Symbols with digits, spaces or punctuation other than currency signs must be quoted
+            PriceFormat::Ledger | PriceFormat::Hledger => {
+                if symbol.is_empty() || symbol.contains('"') {
+                    Err(Error::InvalidCommodity(symbol.into_owned()))
+                } else if symbol
+                    .chars()
+                    .all(|c| c.is_alphabetic() || is_currency_sign(c))
+                {
+                    Ok(symbol.into_owned())
+                } else {
+                    Ok(format!("\"{symbol}\""))
+                }
+            }
+            // Fields with commas or quotes must be quoted, with any quotes doubled
+            PriceFormat::Gnucash | PriceFormat::GnucashQuoted => {
+                if symbol.is_empty() {
+                    Err(Error::InvalidCommodity(symbol.into_owned()))
+                } else if self.format == PriceFormat::GnucashQuoted
+                    || symbol.contains([',', '"', '\n', '\r'])
+                {
+                    Ok(format!("\"{}\"", symbol.replace('"', "\"\"")))
+                } else {
+                    Ok(symbol.into_owned())
+                }
+            }
+        }
+    }
+
+    /// Write the prices which aren't in the existing price file, returning the number written.
+    pub fn write(
+        &self,
+        mut writer: impl Write,
+        prices: impl IntoIterator<Item = Price>,
+    ) -> Result<usize> {
+        let mut written = 0;
+
+        match self.format {
+            PriceFormat::Gnucash if self.header => {
+                writeln!(writer, "date,commodity,currency,price")?
+            }
+            PriceFormat::GnucashQuoted if self.header => {
+                writeln!(writer, "\"date\",\"commodity\",\"currency\",\"price\"")?
+            }
+            _ => {}
+        }
+
+        for p in prices {
+            let commodity = self.commodity(&p.commodity)?;
+            let currency = self.commodity(&p.currency)?;
+            if self.existing.contains(&(
+                *p.date,
+                commodity.trim_matches('"').to_owned(),
+                currency.trim_matches('"').to_owned(),
+            )) {
+                continue;
+            }
+
+            let decimals = self.decimals;
+            match self.format {
+                PriceFormat::Ledger => writeln!(
+                    writer,
+                    "P {} {commodity} {:.decimals$} {currency}",
+                    p.date.format("%Y/%m/%d"),
+                    p.price
+                )?,
+                PriceFormat::Hledger => writeln!(
+                    writer,
+                    "P {} {commodity} {:.decimals$} {currency}",
+                    p.date, p.price
+                )?,
+                PriceFormat::Beancount => writeln!(
+                    writer,
+                    "{} price {commodity} {:.decimals$} {currency}",
+                    p.date, p.price
+                )?,
+                PriceFormat::Gnucash => writeln!(
+                    writer,
+                    "{},{commodity},{currency},{:.decimals$}",
+                    p.date, p.price
+                )?,
+                PriceFormat::GnucashQuoted => writeln!(
+                    writer,
+                    "\"{}\",{commodity},{currency},{:.decimals$}",
+                    p.date, p.price
+                )?,
+            }
+            written += 1;
+        }
+
+        Ok(written)
+    }
+}
+
+/// Whether the character is a currency sign, e.g. `$` or `€`.
+fn is_currency_sign(c: char) -> bool {
+    matches!(
+        c,
+        '$' | '£' | '¥' | '€' | '₹' | '₩' | '₪' | '₱' | '₺' | '₽' | '¢'
+    )
+}
+
+/// Parse the date, commodity symbol and currency symbol of a price directive in any of the formats,
+/// e.g. `P 2024/01/02 00:00:00 USD 0.91 EUR`, `P 2024-01-02 USD €0.91`,
+/// `2024-01-02 price USD 0.91 EUR` or `"2024-01-02","USD","EUR",0.91`.
+fn parse_directive(line: &str) -> Option<(NaiveDate, String, String)> {
+    let parse_date = |s: &str| NaiveDate::parse_from_str(&s.replace(['/', '.'], "-"), "%Y-%m-%d");
+
+    // CSV of the GnuCash formats, where the header fails to parse as a date
+    let fields: Vec<&str> = line
+        .split(',')
+        .map(|f| f.trim().trim_matches('"'))
+        .collect();
+    if let [date, commodity, currency, _] = fields[..]
+        && let Ok(date) = parse_date(date)
+    {
+        return Some((date, commodity.to_owned(), currency.to_owned()));
+    }
+    let mut words = line.split_whitespace();
+
+    let (date, mut rest) = match words.next()? {
+        "P" => (parse_date(words.next()?).ok()?, words),
+        date => {
+            let date = parse_date(date).ok()?;
+            (words.next()? == "price").then_some((date, words))?
+        }
+    };
+
+    // Ledger allows a time after the date
+    let mut commodity = rest.next()?;
+    if commodity.contains(':') {
+        commodity = rest.next()?;
+    }
+    // Quoted symbols may contain spaces
+    let commodity = match commodity.strip_prefix('"') {
+        Some(quoted) => {
+            let mut symbol = quoted.to_owned();
+            while !symbol.ends_with('"') {
+                symbol.push(' ');
+                symbol.push_str(rest.next()?);
+            }
+            symbol.pop();
+            symbol
+        }
+        None => commodity.to_owned(),
+    };
+
+    // The currency may be before or after the price, and be followed by a comment
+    let amount = rest.collect::<Vec<_>>().join(" ");
+    let amount = amount.split(';').next().unwrap_or_default();
+    let currency = amount
+        .trim_matches(|c: char| c.is_ascii_digit() || c.is_whitespace() || ".,-+".contains(c))
+        .trim_matches('"');
+    if currency.is_empty() {
+        return None;
+    }
+
+    Some((date, commodity, currency.to_owned()))
+}
+
+#[cfg(test)]
+mod tests_prices {
+    use std::str::FromStr;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn response() -> period::Response {
+        serde_json::from_str(
+            r#"{"base": "EUR", "amount": 2.0, "start_date": "2024-01-08", "end_date": "2024-01-09",
+                "rates": {"2024-01-08": {"GBP": 1.6, "USD": 2.5}, "2024-01-09": {"USD": 3.2}}}"#,
+        )
+        .unwrap()
+    }
+
+    fn write(writer: &PriceWriter) -> String {
+        let mut out = Vec::new();
+        writer.write(&mut out, response().prices()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(
+            PriceFormat::from_str("HLedger").unwrap(),
+            PriceFormat::Hledger
+        );
+        assert_eq!(
+            PriceFormat::from_str("GnuCash-Quoted").unwrap(),
+            PriceFormat::GnucashQuoted
+        );
+        assert_eq!(
+            PriceFormat::VARIANTS,
+            [
+                "ledger",
+                "hledger",
+                "beancount",
+                "gnucash",
+                "gnucash-quoted"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prices() {
+        let writer = PriceWriter::new(PriceFormat::Ledger).with_decimals(4);
+        assert_eq!(
+            write(&writer),
+            "P 2024/01/08 GBP 1.2500 EUR\nP 2024/01/08 USD 0.8000 EUR\nP 2024/01/09 USD 0.6250 EUR\n"
+        );
+
+        let writer = PriceWriter::new(PriceFormat::Beancount).with_decimals(2);
+        assert_eq!(
+            write(&writer),
+            "2024-01-08 price GBP 1.25 EUR\n2024-01-08 price USD 0.80 EUR\n2024-01-09 price USD 0.62 EUR\n"
+        );
+    }
+
+    #[test]
+    fn test_gnucash() {
+        let writer = PriceWriter::new(PriceFormat::Gnucash).with_decimals(4);
+        assert_eq!(
+            write(&writer),
+            "date,commodity,currency,price\n2024-01-08,GBP,EUR,1.2500\n\
+             2024-01-08,USD,EUR,0.8000\n2024-01-09,USD,EUR,0.6250\n"
+        );
+
+        let writer = PriceWriter::new(PriceFormat::GnucashQuoted)
+            .with_decimals(2)
+            .with_symbol(Currency::GBP, "GBP \"2\"");
+        assert_eq!(
+            write(&writer),
+            "\"date\",\"commodity\",\"currency\",\"price\"\n\"2024-01-08\",\"GBP \"\"2\"\"\",\"EUR\",1.25\n\
+             \"2024-01-08\",\"USD\",\"EUR\",0.80\n\"2024-01-09\",\"USD\",\"EUR\",0.62\n"
+        );
+
+        // The header isn't repeated when appending
+        let existing = "date,commodity,currency,price\n\"2024-01-08\",\"GBP\",\"EUR\",1.25\n";
+        let writer = PriceWriter::new(PriceFormat::Gnucash)
+            .with_decimals(2)
+            .with_existing(existing.as_bytes())
+            .unwrap();
+        assert_eq!(
+            write(&writer),
+            "2024-01-08,USD,EUR,0.80\n2024-01-09,USD,EUR,0.62\n"
+        );
+    }
+
+    #[test]
+    fn test_symbols() {
+        let writer = PriceWriter::new(PriceFormat::Hledger)
+            .with_decimals(2)
+            .with_symbol(Currency::USD, "$")
+            .with_symbol(Currency::EUR, "€")
+            .with_symbol(Currency::GBP, "GBP 2");
+        assert_eq!(
+            write(&writer),
+            "P 2024-01-08 \"GBP 2\" 1.25 €\nP 2024-01-08 $ 0.80 €\nP 2024-01-09 $ 0.62 €\n"
+        );
+
+        // Beancount commodities can't be symbols
+        let writer = PriceWriter::new(PriceFormat::Beancount).with_symbol(Currency::USD, "$");
+        assert!(matches!(
+            writer.write(Vec::new(), response().prices()),
+            Err(Error::InvalidCommodity(_))
+        ));
+    }
+
+    #[test]
+    fn test_existing() {
+        let existing = "; prices\n\
+            P 2024/01/08 00:00:00 \"GBP 2\" 1.25 EUR\n\
+            2024-01-09 price USD 0.62 EUR\n\
+            2024-01-09 open Assets:Cash\n";
+        let writer = PriceWriter::new(PriceFormat::Hledger)
+            .with_symbol(Currency::GBP, "GBP 2")
+            .with_existing(existing.as_bytes())
+            .unwrap();
+
+        assert_eq!(write(&writer), "P 2024-01-08 USD 0.800000 EUR\n");
+    }
+
+    #[test]
+    fn test_existing_other_currency() {
+        // Prices of the same commodities in other currencies, e.g. from a period with another base
+        let existing = "P 2024-01-08 USD 0.78 GBP\n\
+            P 2024-01-09 USD £0.78\n\
+            P 2024-01-09 USD €0.625 ; in EUR\n";
+        let writer = PriceWriter::new(PriceFormat::Hledger)
+            .with_decimals(2)
+            .with_existing(existing.as_bytes())
+            .unwrap();
+        assert_eq!(
+            write(&writer),
+            "P 2024-01-08 GBP 1.25 EUR\nP 2024-01-08 USD 0.80 EUR\nP 2024-01-09 USD 0.62 EUR\n"
+        );
+
+        let writer = writer.with_symbol(Currency::EUR, "€");
+        assert_eq!(
+            write(&writer),
+            "P 2024-01-08 GBP 1.25 €\nP 2024-01-08 USD 0.80 €\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_directive() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+
+        let directive = |commodity: &str, currency: &str| {
+            Some((date, String::from(commodity), String::from(currency)))
+        };
+        assert_eq!(
+            parse_directive("P 2024-01-02 USD 0.91 EUR"),
+            directive("USD", "EUR")
+        );
+        assert_eq!(
+            parse_directive("P 2024.01.02 \"A B C\" 1 \"D 2\""),
+            directive("A B C", "D 2")
+        );
+        assert_eq!(
+            parse_directive("P 2024/01/02 00:00:00 $ €1,000.5 ; comment"),
+            directive("$", "€")
+        );
+        assert_eq!(
+            parse_directive("2024-01-02 price USD 0.91 EUR"),
+            directive("USD", "EUR")
+        );
+        assert_eq!(
+            parse_directive("\"2024-01-02\",\"USD\",\"EUR\",0.91"),
+            directive("USD", "EUR")
+        );
+        assert_eq!(
+            parse_directive("P 2024-01-02 X 1,000,000,000 EUR"),
+            directive("X", "EUR")
+        );
+        assert_eq!(parse_directive("date,commodity,currency,price"), None);
+        assert_eq!(parse_directive("P 2024-01-02 USD 0.91"), None);
+        assert_eq!(parse_directive("2024-01-02 * \"Groceries\""), None);
+        assert_eq!(parse_directive("P USD"), None);
+        assert_eq!(parse_directive(""), None);
+    }
+}
@@ -0,0 +1,59 @@
+//! Clock access which also works when compiled for WebAssembly, where [`std::time`] and `chrono`'s
+//! clock panic at runtime, by reading the time from JavaScript's `Date` instead.
+
+use chrono::{DateTime, NaiveDate, Utc};
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::Instant;
+
+/// Get the current date and time in UTC.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now() -> DateTime<Utc> {
+    Utc::now()
+}
+
+/// Get the current date and time in UTC.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now() -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(js_sys::Date::now() as i64)
+        .expect("JavaScript date out of range")
+}
+
+/// Get the current date in the local timezone.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
+
+/// Get the current date in the local timezone.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn today() -> NaiveDate {
+    let date = js_sys::Date::new_0();
+    NaiveDate::from_ymd_opt(
+        date.get_full_year() as i32,
+        date.get_month() + 1,
+        date.get_date(),
+    )
+    .expect("JavaScript date out of range")
+}
+
+#[cfg(test)]
+mod tests_time {
+    use chrono::{Local, TimeDelta};
+
+    use super::*;
+
+    #[test]
+    fn test_now() {
+        assert!((Utc::now() - now()).abs() < TimeDelta::seconds(1));
+    }
+
+    #[test]
+    fn test_today() {
+        // Allow for the date changing between the calls
+        let before = Local::now().date_naive();
+        let today = today();
+        assert!(today == before || today == Local::now().date_naive());
+    }
+}
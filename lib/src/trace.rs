@@ -0,0 +1,37 @@
+//! Internal instrumentation helpers, which compile to nothing unless the `tracing` feature is
+//! enabled.
+
+/// Record a value for a field of the current span.
+///
+/// Prefix the value with `%` or `?` to record it using its [`std::fmt::Display`] or
+/// [`std::fmt::Debug`] implementation respectively, as with the [`tracing`] macros.
+macro_rules! record {
+    ($field:literal, %$value:expr) => {
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record($field, tracing::field::display(&$value));
+        #[cfg(not(feature = "tracing"))]
+        let _ = &$value;
+    };
+    ($field:literal, ?$value:expr) => {
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record($field, tracing::field::debug(&$value));
+        #[cfg(not(feature = "tracing"))]
+        let _ = &$value;
+    };
+    ($field:literal, $value:expr) => {
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record($field, $value);
+        #[cfg(not(feature = "tracing"))]
+        let _ = &$value;
+    };
+}
+
+/// Emit an event at the given level, e.g. `event!(debug, "message")`.
+macro_rules! event {
+    ($level:ident, $($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)+);
+    };
+}
+
+pub(crate) use {event, record};
@@ -0,0 +1,61 @@
+mod shared;
+use lib_frankfurter::{
+    Currency, ValidDate,
+    alert::{Condition, Pair, Rule},
+    api::convert,
+};
+use pretty_assertions::assert_eq;
+use shared::get_server;
+
+#[tokio::test]
+async fn alert_rules() {
+    let server = get_server();
+    let pair: Pair = "EUR/USD".parse().unwrap();
+    // Fixed dates, as the requests of rules are relative to today
+    let start_date = "2024-01-02".parse::<ValidDate>().unwrap();
+    let end_date = "2024-06-28".parse::<ValidDate>().unwrap();
+
+    // PERIOD
+    let rule = Rule::new(pair.clone(), Condition::DailyMove(1000.0)).unwrap();
+    let response = server
+        .period(
+            rule.period_request()
+                .with_start_date("2024-06-18".parse().unwrap())
+                .with_end_date(end_date),
+        )
+        .await
+        .unwrap();
+    let evaluation = rule.check_period(&response).unwrap();
+    assert_eq!(
+        Some(evaluation.date),
+        response.rates.keys().last().map(|d| **d)
+    );
+    assert!(evaluation.reference.is_some());
+    assert!(!evaluation.fired);
+
+    let rule = Rule::new(pair.clone(), Condition::High(100)).unwrap();
+    let response = server
+        .period(
+            rule.period_request()
+                .with_start_date(start_date)
+                .with_end_date(end_date),
+        )
+        .await
+        .unwrap();
+    assert!(response.rates.len() > 100);
+    assert!(rule.check_period(&response).is_some());
+
+    // CONVERT
+    let response = server
+        .convert(
+            convert::Request::default()
+                .with_targets(vec![Currency::USD])
+                .with_date(end_date),
+        )
+        .await
+        .unwrap();
+    let rule = Rule::new(pair.clone(), Condition::Above(0.01)).unwrap();
+    assert!(rule.check_convert(&response).unwrap().fired);
+    let rule = Rule::new(pair, Condition::DailyMove(1.0)).unwrap();
+    assert!(rule.check_convert(&response).is_none());
+}
@@ -1,4 +1,6 @@
 mod shared;
+use lib_frankfurter::{api::ServerClient, url::Url};
+use pretty_assertions::assert_eq;
 use shared::{get_invalid_server, get_server};
 
 #[tokio::test]
@@ -19,3 +21,26 @@ async fn endpoint_currencies() {
     let server = get_invalid_server();
     assert!(server.currencies(Default::default()).await.is_err())
 }
+
+#[tokio::test]
+async fn health() {
+    let health = get_server().health().await;
+    assert!(health.reachable);
+    assert!(health.error.is_none());
+    assert!(health.latest_date.is_some());
+    assert_eq!(health.api_version.as_deref(), Some("v1"));
+
+    // ERROR RESPONSE FROM API
+    let health = get_invalid_server().health().await;
+    assert!(health.reachable);
+    assert!(health.error.as_ref().is_some_and(|e| e.is_not_found()));
+    assert!(health.is_stale());
+    assert!(!health.is_healthy());
+
+    // UNREACHABLE API
+    let health = ServerClient::new(Url::parse("http://localhost:1").unwrap())
+        .health()
+        .await;
+    assert!(!health.reachable);
+    assert!(!health.is_healthy());
+}
@@ -0,0 +1,33 @@
+mod shared;
+use lib_frankfurter::{Currency, ValidDate, calc::Calculation};
+use pretty_assertions::assert_eq;
+use shared::get_server;
+
+#[tokio::test]
+async fn calculation() {
+    let server = get_server();
+    let calculation: Calculation = "120 USD + 45.50 GBP - 10 EUR in EUR".parse().unwrap();
+    let date = "2024-01-09".parse::<ValidDate>().unwrap();
+
+    let response = server
+        .convert(calculation.convert_request(Some(date)))
+        .await
+        .unwrap();
+    let evaluation = calculation.evaluate(&response).unwrap();
+
+    assert_eq!(evaluation.target, Currency::EUR);
+    assert_eq!(evaluation.date, date);
+    assert_eq!(
+        evaluation
+            .terms
+            .iter()
+            .map(|t| t.currency.clone())
+            .collect::<Vec<_>>(),
+        [Currency::USD, Currency::GBP, Currency::EUR]
+    );
+    assert_eq!(evaluation.terms[2].converted, 10.0);
+    assert_eq!(
+        evaluation.total,
+        evaluation.terms[0].converted + evaluation.terms[1].converted - 10.0
+    );
+}
@@ -0,0 +1,70 @@
+mod shared;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use lib_frankfurter::{
+    ValidDate,
+    api::{
+        ServerClient, convert,
+        middleware::{OnResponse, ResponseInfo},
+    },
+    url::Url,
+};
+use pretty_assertions::assert_eq;
+use shared::fixture_server;
+
+/// URL where no API is running.
+const UNREACHABLE_URL: &str = "http://localhost:1";
+
+#[tokio::test]
+async fn failover() {
+    // Failover needs real requests, so the fixtures are served over HTTP rather than replayed
+    let url = fixture_server();
+    let request = convert::Request::default().with_date("2024-08-20".parse::<ValidDate>().unwrap());
+    let ports = Arc::new(Mutex::new(Vec::new()));
+    let record_port = {
+        let ports = ports.clone();
+        OnResponse(move |resp: &ResponseInfo<'_>| {
+            ports.lock().unwrap().push(resp.url.port().unwrap())
+        })
+    };
+
+    let server = ServerClient::builder(Url::parse(UNREACHABLE_URL).unwrap())
+        .fallback_url(url.clone())
+        .failover_cooldown(Duration::from_secs(60))
+        .middleware(record_port)
+        .build()
+        .unwrap();
+
+    // FAILS OVER TO THE FALLBACK
+    assert!(server.is_server_available().await);
+    assert!(server.convert(request.clone()).await.is_ok());
+    assert!(server.currencies(Default::default()).await.is_ok());
+
+    // Responses are only received from the fallback
+    let port = url.port().unwrap();
+    assert_eq!(*ports.lock().unwrap(), vec![port, port, port]);
+
+    // ALL ENDPOINTS UNREACHABLE
+    let server = ServerClient::builder(Url::parse(UNREACHABLE_URL).unwrap())
+        .fallback_url(Url::parse(UNREACHABLE_URL).unwrap())
+        .build()
+        .unwrap();
+    assert!(!server.is_server_available().await);
+    assert!(
+        server
+            .convert(request.clone())
+            .await
+            .unwrap_err()
+            .is_network()
+    );
+
+    // NO FAILOVER FOR CLIENT ERRORS
+    let server = ServerClient::builder(url.join("invalid").unwrap())
+        .fallback_url(url)
+        .build()
+        .unwrap();
+    assert!(server.convert(request).await.unwrap_err().is_not_found());
+}
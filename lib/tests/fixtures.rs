@@ -0,0 +1,86 @@
+mod shared;
+use lib_frankfurter::{
+    Error, ValidDate,
+    api::{ServerClient, convert, fixtures::FixtureMode, period},
+    url::Url,
+};
+use pretty_assertions::assert_eq;
+use shared::{fixture_server, redirect_server};
+
+#[tokio::test]
+async fn record_and_replay() {
+    let dir = std::env::temp_dir().join(format!("frankfurter-fixtures-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    // Requests which have committed fixtures, as they are recorded from the fixture server
+    let convert_request = convert::Request::default()
+        .with_date("2024-08-20".parse::<ValidDate>().unwrap())
+        .with_targets(vec![
+            "AUD".parse().unwrap(),
+            "DKK".parse().unwrap(),
+            "ZAR".parse().unwrap(),
+        ]);
+    let period_request = period::Request::default()
+        .with_start_date("2024-10-07".parse::<ValidDate>().unwrap())
+        .with_end_date("2024-10-11".parse::<ValidDate>().unwrap());
+
+    // RECORD
+    let recorder = ServerClient::new(fixture_server()).with_fixtures(&dir, FixtureMode::Record);
+    let recorded_convert = recorder.convert(convert_request.clone()).await.unwrap();
+    let recorded_period = recorder.period(period_request.clone()).await.unwrap();
+    assert!(recorder.currencies(Default::default()).await.is_ok());
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 3);
+
+    // REPLAY - from an unreachable API
+    let replayer = ServerClient::new(Url::parse("http://localhost:1").unwrap())
+        .with_fixtures(&dir, FixtureMode::Replay);
+    assert_eq!(
+        replayer.convert(convert_request.clone()).await.unwrap(),
+        recorded_convert
+    );
+    assert_eq!(
+        replayer.period(period_request).await.unwrap(),
+        recorded_period
+    );
+    assert!(replayer.currencies(Default::default()).await.is_ok());
+
+    // REPLAY - unknown request
+    let err = replayer
+        .convert(convert_request.with_targets(vec!["JPY".parse().unwrap()]))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::MissingFixture { request, .. } if request == "/v1/2024-08-20?symbols=JPY"),
+        "{err:?}"
+    );
+    assert!(!err.is_network() && !err.is_retryable());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn record_and_replay_redirect() {
+    let dir = std::env::temp_dir().join(format!(
+        "frankfurter-fixtures-redirect-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    let request = convert::Request::default()
+        .with_date("2024-08-20".parse::<ValidDate>().unwrap())
+        .with_targets(vec![
+            "AUD".parse().unwrap(),
+            "DKK".parse().unwrap(),
+            "ZAR".parse().unwrap(),
+        ]);
+
+    // RECORD - from `/old/v1/...`, redirected to `/v1/...` of the fixture server
+    let url = redirect_server(fixture_server());
+    let recorder = ServerClient::new(url.clone()).with_fixtures(&dir, FixtureMode::Record);
+    let recorded = recorder.convert(request.clone()).await.unwrap();
+
+    // REPLAY - of the request before it was redirected
+    let replayer = ServerClient::new(url).with_fixtures(&dir, FixtureMode::Replay);
+    assert_eq!(replayer.convert(request).await.unwrap(), recorded);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,46 @@
+mod shared;
+use std::sync::{Arc, Mutex};
+
+use lib_frankfurter::{
+    api::middleware::{OnRequest, OnResponse, ResponseInfo},
+    reqwest::{Request, StatusCode},
+};
+use pretty_assertions::assert_eq;
+use shared::{get_invalid_server, get_server};
+
+#[tokio::test]
+async fn middleware() {
+    let statuses = Arc::new(Mutex::new(Vec::new()));
+    let record_status = {
+        let statuses = statuses.clone();
+        OnResponse(move |resp: &ResponseInfo<'_>| statuses.lock().unwrap().push(resp.status))
+    };
+
+    // REWRITE REQUEST
+    let server = get_server()
+        .with_middleware(OnRequest(|req: &mut Request| {
+            if req.url().path().ends_with("latest") {
+                req.url_mut().set_path("/v1/invalid");
+            }
+        }))
+        .with_middleware(record_status.clone());
+
+    assert!(server.currencies(Default::default()).await.is_ok());
+    assert!(
+        server
+            .convert(Default::default())
+            .await
+            .unwrap_err()
+            .is_not_found()
+    );
+    assert_eq!(
+        *statuses.lock().unwrap(),
+        vec![StatusCode::OK, StatusCode::NOT_FOUND]
+    );
+
+    // ERROR RESPONSE FROM API
+    statuses.lock().unwrap().clear();
+    let server = get_invalid_server().with_middleware(record_status);
+    assert!(server.currencies(Default::default()).await.is_err());
+    assert_eq!(*statuses.lock().unwrap(), vec![StatusCode::NOT_FOUND]);
+}
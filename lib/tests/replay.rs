@@ -0,0 +1,138 @@
+//! Tests of the endpoints which replay the committed fixtures, so they run without the local API.
+//!
+//! Requests use fixed dates so that their fixtures don't change, re-record them with
+//! `FRANKFURTER_FIXTURES=record` after adding or changing a request.
+
+mod shared;
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate};
+use lib_frankfurter::{
+    Currency, CurrencyValue, ValidDate,
+    api::{convert, period},
+};
+use pretty_assertions::assert_eq;
+use shared::{get_replay_invalid_server, get_replay_server};
+
+#[tokio::test]
+async fn replay_server_available() {
+    assert!(get_replay_server().is_server_available().await);
+}
+
+#[tokio::test]
+async fn replay_convert() {
+    let server = get_replay_server();
+    let make_request = |request: convert::Request| async { server.convert(request).await.unwrap() };
+    let date = ValidDate::try_from(NaiveDate::from_ymd_opt(2024, 8, 20).unwrap()).unwrap();
+
+    // BASIC
+    let res = make_request(convert::Request::default().with_date(date)).await;
+    assert_eq!(res.base, Currency::EUR);
+    assert!(res.rates.len() > 10);
+    assert_eq!(res.amount, CurrencyValue::try_from(1.0).unwrap());
+    assert_eq!(res.date, date);
+
+    // BASE CURRENCY AND AMOUNT
+    let base = Currency::USD;
+    let amount = CurrencyValue::try_from(4.0).unwrap();
+    let res = make_request(
+        convert::Request::default()
+            .with_base(base.clone())
+            .with_amount(amount)
+            .with_date(date),
+    )
+    .await;
+    assert_eq!(res.base, base);
+    assert_eq!(res.amount, amount);
+
+    // TARGETS
+    let targets = vec![Currency::AUD, Currency::DKK, Currency::ZAR];
+    let res = make_request(
+        convert::Request::default()
+            .with_targets(targets.clone())
+            .with_date(date),
+    )
+    .await;
+    assert_eq!(res.rates.len(), targets.len());
+
+    // ERROR RESPONSE FROM API
+    let server = get_replay_invalid_server();
+    assert!(
+        server
+            .convert(convert::Request::default().with_date(date))
+            .await
+            .is_err()
+    )
+}
+
+#[tokio::test]
+async fn replay_period() {
+    let server = get_replay_server();
+    let make_request = |request: period::Request| async { server.period(request).await.unwrap() };
+    let start_date = ValidDate::from_str("2024-10-07").unwrap();
+    let end_date = ValidDate::from_str("2024-10-11").unwrap();
+
+    // BASIC
+    let res = make_request(
+        period::Request::default()
+            .with_start_date(start_date)
+            .with_end_date(end_date),
+    )
+    .await;
+    assert_eq!(res.start_date, start_date);
+    assert_eq!(res.end_date.unwrap(), end_date);
+    assert_eq!(res.base, Currency::EUR);
+    assert!(res.rates.last_key_value().unwrap().1.len() > 10);
+    assert_eq!(
+        res.rates.len(),
+        // Start -> end date (inclusive)
+        (end_date.num_days_from_ce() - start_date.num_days_from_ce() + 1) as usize
+    );
+
+    // BASE CURRENCY AND AMOUNT
+    let base = Currency::KRW;
+    let amount = CurrencyValue::try_from(10.0).unwrap();
+    let res = make_request(
+        period::Request::default()
+            .with_base(base.clone())
+            .with_amount(amount)
+            .with_start_date(start_date)
+            .with_end_date(end_date),
+    )
+    .await;
+    assert_eq!(res.base, base);
+    assert_eq!(res.amount, amount);
+
+    // TARGETS
+    let targets = vec![Currency::CHF, Currency::CAD, Currency::CNY];
+    let res = make_request(
+        period::Request::default()
+            .with_targets(targets.clone())
+            .with_start_date(start_date)
+            .with_end_date(end_date),
+    )
+    .await;
+    assert_eq!(res.rates.last_key_value().unwrap().1.len(), targets.len());
+
+    // ERROR RESPONSE FROM API
+    let server = get_replay_invalid_server();
+    assert!(
+        server
+            .period(
+                period::Request::default()
+                    .with_start_date(start_date)
+                    .with_end_date(end_date)
+            )
+            .await
+            .is_err()
+    )
+}
+
+#[tokio::test]
+async fn replay_currencies() {
+    let res = get_replay_server()
+        .currencies(Default::default())
+        .await
+        .unwrap();
+    assert!(res.0.len() > 10);
+}
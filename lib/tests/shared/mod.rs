@@ -1,13 +1,26 @@
-use std::sync::LazyLock;
+#![allow(dead_code)]
 
-use lib_frankfurter::api;
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::LazyLock,
+};
+
+use lib_frankfurter::{
+    api::{self, fixtures::FixtureMode},
+    reqwest::StatusCode,
+};
 use url::Url;
 
 /// URL for locally hosted API
-static URL: LazyLock<Url> = LazyLock::new(|| Url::parse("http://localhost:8080").unwrap());
-static INVALID_URL: LazyLock<Url> =
+pub static URL: LazyLock<Url> = LazyLock::new(|| Url::parse("http://localhost:8080").unwrap());
+pub static INVALID_URL: LazyLock<Url> =
     LazyLock::new(|| Url::parse("http://localhost:8080/invalid").unwrap());
 
+/// Directory of the fixtures replayed by [`get_replay_server`] and [`fixture_server`].
+pub const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
 pub fn get_server() -> api::ServerClient {
     api::ServerClient::new(URL.clone())
 }
@@ -15,3 +28,95 @@ pub fn get_server() -> api::ServerClient {
 pub fn get_invalid_server() -> api::ServerClient {
     api::ServerClient::new(INVALID_URL.clone())
 }
+
+/// Replay the fixtures, or record them from the local API if `FRANKFURTER_FIXTURES=record` is set.
+fn with_fixtures(server_client: api::ServerClient) -> api::ServerClient {
+    let mode = match FixtureMode::from_env() {
+        Some(FixtureMode::Record) => FixtureMode::Record,
+        _ => FixtureMode::Replay,
+    };
+    server_client.with_fixtures(FIXTURES_DIR, mode)
+}
+
+pub fn get_replay_server() -> api::ServerClient {
+    with_fixtures(get_server())
+}
+
+pub fn get_replay_invalid_server() -> api::ServerClient {
+    with_fixtures(get_invalid_server())
+}
+
+/// Start a local HTTP server which replays the recorded fixtures, for tests of behaviour over the
+/// network, e.g. failover, which can't run without an API. Returns the URL of the server.
+///
+/// Requests without a fixture get an empty `404 Not Found` response.
+pub fn fixture_server() -> Url {
+    let fixtures: HashMap<String, (u16, String)> = std::fs::read_dir(FIXTURES_DIR)
+        .unwrap()
+        .map(|entry| {
+            let fixture: serde_json::Value =
+                serde_json::from_slice(&std::fs::read(entry.unwrap().path()).unwrap()).unwrap();
+            (
+                fixture["request"].as_str().unwrap().to_owned(),
+                (
+                    fixture["status"].as_u64().unwrap() as u16,
+                    fixture["body"].as_str().unwrap().to_owned(),
+                ),
+            )
+        })
+        .collect();
+
+    serve(move |target| {
+        let (status, body) = fixtures
+            .get(target)
+            .cloned()
+            .unwrap_or((404, String::new()));
+        let reason = StatusCode::from_u16(status)
+            .ok()
+            .and_then(|s| s.canonical_reason())
+            .unwrap_or_default();
+        format!(
+            "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    })
+}
+
+/// Start a local HTTP server which permanently redirects every request under `/old` to the same
+/// path and query under the given URL. Returns the URL of the server, including `/old`.
+pub fn redirect_server(to: Url) -> Url {
+    let url = serve(move |target| {
+        let location = format!(
+            "{}{}",
+            to.as_str().trim_end_matches('/'),
+            target.strip_prefix("/old").unwrap_or(target)
+        );
+        format!(
+            "HTTP/1.1 308 Permanent Redirect\r\nLocation: {location}\r\n\
+             Content-Length: 0\r\nConnection: close\r\n\r\n"
+        )
+    });
+
+    url.join("old").unwrap()
+}
+
+/// Start a local HTTP server on a free port, which responds to each request with the response
+/// returned for its path and query. Returns the URL of the server.
+fn serve(respond: impl Fn(&str) -> String + Send + 'static) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut lines = BufReader::new(&stream).lines().map_while(Result::ok);
+            // e.g. `GET /v1/latest HTTP/1.1`, followed by the headers until an empty line
+            let request_line = lines.next().unwrap_or_default();
+            lines.take_while(|line| !line.is_empty()).for_each(drop);
+
+            let target = request_line.split(' ').nth(1).unwrap_or_default();
+            let _ = stream.write_all(respond(target).as_bytes());
+        }
+    });
+
+    url
+}
@@ -0,0 +1,42 @@
+mod shared;
+use std::{pin::pin, time::Duration};
+
+use futures_util::StreamExt;
+use lib_frankfurter::{Currency, api::convert};
+use pretty_assertions::assert_eq;
+use shared::{get_invalid_server, get_server};
+
+#[tokio::test]
+async fn watch_latest() {
+    let server = get_server();
+    let request = convert::Request::default().with_targets(vec![Currency::USD]);
+    let latest = server.convert(request.clone()).await.unwrap();
+
+    // YIELDS THE CURRENT RATES FIRST
+    let mut rates = pin!(
+        server
+            .watch_latest(request)
+            .with_retry_interval(Duration::from_millis(50))
+            .into_stream()
+    );
+    assert_eq!(rates.next().await.unwrap().unwrap(), latest);
+
+    // Nothing more until the date of the rates changes
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), rates.next())
+            .await
+            .is_err()
+    );
+
+    // ERRORS ARE YIELDED AND RETRIED
+    let server = get_invalid_server();
+    let mut rates = pin!(
+        server
+            .watch_latest(Default::default())
+            .with_retry_interval(Duration::from_millis(50))
+            .into_stream()
+    );
+    for _ in 0..2 {
+        assert!(rates.next().await.unwrap().unwrap_err().is_not_found());
+    }
+}